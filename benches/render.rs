@@ -0,0 +1,114 @@
+//! Measures the allocations and time it takes to hand every shape of a large canvas to a renderer.
+//!
+//! Run with `cargo bench --bench render`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use barium::{Canvas, Color, LineEnd, Renderer, Shape, Stroke, Vec2};
+
+/// Wraps the system allocator to count every allocation.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// A renderer that only looks at the points it is given.
+struct SumRenderer(Vec2);
+
+impl Renderer for SumRenderer {
+    type Output = Vec2;
+
+    fn render(&mut self, shape: &Shape) {
+        self.0 += shape.points.iter().sum::<Vec2>();
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.0
+    }
+}
+
+/// Run `f` a few times, returning the allocations and the fastest time of a single run.
+fn measure<F: FnMut() -> Vec2>(mut f: F) -> (usize, Duration) {
+    let mut fastest = Duration::MAX;
+    let mut allocations = 0;
+
+    for _ in 0..5 {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        black_box(f());
+        fastest = fastest.min(start.elapsed());
+        allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    }
+
+    (allocations, fastest)
+}
+
+fn main() {
+    // A million points, split into ten thousand shapes.
+    let mut canvas = Canvas::default();
+    canvas.zoom_camera(1.5);
+    canvas.rotate_camera(0.3);
+
+    for i in 0..10_000 {
+        let points: Vec<Vec2> = (0..100)
+            .map(|n| Vec2::new(i as f32 * 0.001, (n as f32 * 0.1).sin()))
+            .collect();
+
+        canvas.draw_shape(
+            points,
+            Some(Stroke::new(Color::black(), 0.01, LineEnd::Round)),
+            None,
+        );
+    }
+
+    // How Canvas::render used to transform shapes: a full clone of each one.
+    let (cloned_allocations, cloned_time) = measure(|| {
+        let mut renderer = SumRenderer(Vec2::ZERO);
+        renderer.begin(&canvas.scene_info());
+
+        for shape in canvas.as_raw() {
+            let mut shape = shape.clone();
+            for point in &mut shape.points {
+                *point = canvas.to_camera_space(*point);
+            }
+            renderer.render(&shape);
+        }
+
+        renderer.finalize()
+    });
+
+    let (scratch_allocations, scratch_time) = measure(|| canvas.render(SumRenderer(Vec2::ZERO)));
+
+    println!("{:<24}{:>12}{:>16}", "", "allocations", "time");
+    println!(
+        "{:<24}{:>12}{:>16?}",
+        "clone per shape", cloned_allocations, cloned_time
+    );
+    println!(
+        "{:<24}{:>12}{:>16?}",
+        "Canvas::render", scratch_allocations, scratch_time
+    );
+}
@@ -1,9 +1,6 @@
 extern crate barium;
 
-use barium::{
-    renderers::{SkiaRenderer, SvgRenderer},
-    Canvas, Color, LineEnd, Stroke, UVec2, Vec2,
-};
+use barium::{export::ExportOptions, Canvas, Color, LineEnd, Stroke, UVec2, Vec2};
 
 fn main() -> anyhow::Result<()> {
     // Create a canvas, centered on (0, 0). The camera ranges from (-1.0, -1.0) to (1.0, 1.0).
@@ -49,20 +46,10 @@ fn main() -> anyhow::Result<()> {
         None,
     );
 
-    // Save to png
-    let png = canvas.render(SkiaRenderer::new(UVec2::splat(1000), None, true, true));
-    png.save("smile.png")?;
-
-    // Save to svg
-    let svg = canvas.render(SvgRenderer::new(
-        Vec2::splat(1000.0),
-        None,
-        false,
-        false,
-        32,
-    ));
-
-    std::fs::write("smile.svg", svg)?;
+    // Save to png and svg, picking the format from the extension
+    let options = ExportOptions::new(UVec2::splat(1000));
+    canvas.export("smile.png", &options)?;
+    canvas.export("smile.svg", &options)?;
 
     Ok(())
 }
@@ -0,0 +1,47 @@
+use std::{f32::consts::PI, fs::File};
+
+use barium::{
+    animation::{Animation, AnimationOptions},
+    renderers::SkiaRenderer,
+    Canvas, Color, Stroke, UVec2, Vec2,
+};
+
+fn main() -> anyhow::Result<()> {
+    // Create a single spiral
+    let mut points = Vec::with_capacity(2000);
+    for n in 0..2000 {
+        points.push(Vec2::new(
+            n as f32 / 250.0 * (2.0 * PI * n as f32 / 200.0).cos(),
+            n as f32 / 250.0 * (2.0 * PI * n as f32 / 200.0).sin(),
+        ))
+    }
+
+    // Rotate the camera a quarter turn over the course of the animation.
+    let animation = Animation::from_fn(
+        30,
+        |t| {
+            let mut canvas = Canvas::new(1000);
+            canvas.rotate_camera(t * PI / 2.0);
+
+            canvas.draw_shape(
+                points.clone(),
+                Some(Stroke::new(Color::white(), 0.01, barium::LineEnd::Round)),
+                None,
+            );
+
+            canvas
+        },
+        &SkiaRenderer::new(UVec2::splat(400), Some(Color::black()), true, false),
+    );
+
+    animation.encode_gif(
+        File::create("spinning_spiral.gif")?,
+        &AnimationOptions::default(),
+    )?;
+    animation.encode_apng(
+        File::create("spinning_spiral.png")?,
+        &AnimationOptions::default(),
+    )?;
+
+    Ok(())
+}
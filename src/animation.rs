@@ -0,0 +1,485 @@
+use std::{
+    fs, io,
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    error::{EncodingError, ImageFormatHint},
+    Delay, Frame, ImageError, ImageFormat, ImageResult, Rgba, RgbaImage,
+};
+
+use glam::{Vec3, Vec4};
+
+use crate::{renderers::SkiaRenderer, Canvas, Renderer};
+
+/// A sequence of frames rendered from [Canvas] states.
+///
+/// Frames are rasterized with [SkiaRenderer] up front, so they can be encoded several times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    frames: Vec<RgbaImage>,
+}
+
+impl Animation {
+    /// Render each [Canvas], in order, as a frame.
+    ///
+    /// Each frame is rendered with a clone of `renderer`, so they all share its size and background.
+    pub fn from_canvases<'a, I>(canvases: I, renderer: &SkiaRenderer) -> Self
+    where
+        I: IntoIterator<Item = &'a Canvas>,
+    {
+        Self {
+            frames: canvases
+                .into_iter()
+                .map(|canvas| canvas.render(renderer.clone()))
+                .collect(),
+        }
+    }
+
+    /// Render `frame_count` frames, each from the [Canvas] returned by `f`.
+    ///
+    /// `t` ranges from `0.0` up to, but not including, `1.0`, so the last frame leads back into the first.
+    pub fn from_fn<F>(frame_count: usize, f: F, renderer: &SkiaRenderer) -> Self
+    where
+        F: Fn(f32) -> Canvas,
+    {
+        Self {
+            frames: (0..frame_count)
+                .map(|i| f(i as f32 / frame_count as f32).render(renderer.clone()))
+                .collect(),
+        }
+    }
+
+    /// Create an [Animation] from frames that have already been rendered.
+    ///
+    /// All frames should be the same size.
+    pub fn from_frames(frames: Vec<RgbaImage>) -> Self {
+        Self { frames }
+    }
+
+    /// Returns a slice of all the rendered frames.
+    pub fn frames(&self) -> &[RgbaImage] {
+        self.frames.as_slice()
+    }
+
+    /// Returns a [Vec] of all the rendered frames.
+    pub fn into_frames(self) -> Vec<RgbaImage> {
+        self.frames
+    }
+
+    /// Encode the frames as an animated GIF.
+    ///
+    /// GIF stores delays in hundredths of a second, so [frame_delay](AnimationOptions::frame_delay) is rounded down to that precision.
+    pub fn encode_gif<W: Write>(&self, writer: W, options: &AnimationOptions) -> ImageResult<()> {
+        let mut encoder = GifEncoder::new_with_speed(writer, options.quantization_speed);
+
+        match options.loop_count {
+            LoopCount::Infinite => encoder.set_repeat(Repeat::Infinite)?,
+            // GIF counts repetitions after the first play, and omitting the count entirely plays once.
+            LoopCount::Finite(plays) if plays > 1 => {
+                encoder.set_repeat(Repeat::Finite(plays - 1))?
+            }
+            LoopCount::Finite(_) => (),
+        }
+
+        let delay = Delay::from_saturating_duration(options.frame_delay);
+
+        encoder.encode_frames(
+            self.frames
+                .iter()
+                .map(|frame| Frame::from_parts(frame.clone(), 0, 0, delay)),
+        )
+    }
+
+    /// Encode the frames as an animated PNG.
+    ///
+    /// [quantization_speed](AnimationOptions::quantization_speed) is ignored, since APNG stores full color.
+    pub fn encode_apng<W: Write>(&self, writer: W, options: &AnimationOptions) -> ImageResult<()> {
+        let (width, height) = self
+            .frames
+            .first()
+            .map(|frame| frame.dimensions())
+            .unwrap_or((1, 1));
+
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .set_animated(
+                self.frames.len() as u32,
+                match options.loop_count {
+                    LoopCount::Infinite => 0,
+                    LoopCount::Finite(plays) => plays.max(1) as u32,
+                },
+            )
+            .map_err(png_error)?;
+
+        encoder
+            .set_frame_delay(
+                options.frame_delay.as_millis().min(u16::MAX as u128) as u16,
+                1000,
+            )
+            .map_err(png_error)?;
+
+        let mut writer = encoder.write_header().map_err(png_error)?;
+
+        for frame in &self.frames {
+            writer.write_image_data(frame.as_raw()).map_err(png_error)?;
+        }
+
+        writer.finish().map_err(png_error)
+    }
+}
+
+/// Options shared by the [Animation] encoders.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationOptions {
+    /// How long each frame is displayed.
+    pub frame_delay: Duration,
+    /// How many times the animation plays.
+    pub loop_count: LoopCount,
+    /// Speed of the palette quantisation used by GIF, from `1` (best quality) to `30` (fastest).
+    pub quantization_speed: i32,
+}
+
+impl Default for AnimationOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            frame_delay: Duration::from_millis(40),
+            loop_count: LoopCount::Infinite,
+            quantization_speed: 10,
+        }
+    }
+}
+
+/// How many times an [Animation] plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopCount {
+    /// Loop forever.
+    Infinite,
+    /// Play a fixed number of times.
+    Finite(u16),
+}
+
+/// Renders an animation frame by frame, for handing off to an external encoder.
+///
+/// Frames can be written as deterministically numbered files, or streamed as raw RGBA.
+/// Frame `i` is numbered `i` and shows the scene at `i / frame_rate` seconds.
+#[derive(Debug, Clone)]
+pub struct FrameSequence<F> {
+    new_renderer: F,
+    frame_rate: f32,
+    start_index: usize,
+    sub_frames: usize,
+    prefix: String,
+    digits: usize,
+}
+
+impl<F, R> FrameSequence<F>
+where
+    F: Fn() -> R,
+    R: Renderer,
+    R::Output: FrameOutput,
+{
+    /// Create a new [FrameSequence].
+    ///
+    /// `new_renderer` is called once for every frame (and sub-frame) to get a fresh renderer.
+    pub fn new(new_renderer: F, frame_rate: f32) -> Self {
+        Self {
+            new_renderer,
+            frame_rate,
+            start_index: 0,
+            sub_frames: 1,
+            prefix: String::from("frame_"),
+            digits: 5,
+        }
+    }
+
+    /// Modify the index of the first frame, consuming the parent.
+    #[inline]
+    pub fn with_start_index(mut self, start_index: usize) -> Self {
+        self.start_index = start_index;
+        self
+    }
+
+    /// Enable motion blur by averaging `sub_frames` renders spread across each frame's duration, consuming the parent.
+    ///
+    /// A value of `1` or less disables motion blur.
+    #[inline]
+    pub fn with_motion_blur(mut self, sub_frames: usize) -> Self {
+        self.sub_frames = sub_frames.max(1);
+        self
+    }
+
+    /// Modify how frame files are named, consuming the parent.
+    ///
+    /// Files are named `{prefix}{index}.{extension}`, with the index padded with zeros to `digits` digits.
+    #[inline]
+    pub fn with_naming<S: Into<String>>(mut self, prefix: S, digits: usize) -> Self {
+        self.prefix = prefix.into();
+        self.digits = digits;
+        self
+    }
+
+    /// Get the time, in seconds, shown by a frame.
+    pub fn frame_time(&self, index: usize) -> f32 {
+        index as f32 / self.frame_rate
+    }
+
+    /// Get the path a frame is written to.
+    pub fn frame_path<P: AsRef<Path>>(&self, directory: P, index: usize) -> PathBuf {
+        directory.as_ref().join(format!(
+            "{}{:0digits$}.{}",
+            self.prefix,
+            index,
+            R::Output::EXTENSION,
+            digits = self.digits
+        ))
+    }
+
+    /// Render a single frame of a scene, given as a function of time in seconds.
+    ///
+    /// Fails if the frame rate isn't a positive number, since frames wouldn't have a time.
+    pub fn render_frame<S>(&self, scene: &S, index: usize) -> io::Result<R::Output>
+    where
+        S: Fn(f32) -> Canvas,
+    {
+        if !(self.frame_rate.is_finite() && self.frame_rate > 0.0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Frame rate must be positive, got {}", self.frame_rate),
+            ));
+        }
+
+        let sub_frames = (0..self.sub_frames)
+            .map(|i| {
+                let time =
+                    self.frame_time(index) + i as f32 / self.sub_frames as f32 / self.frame_rate;
+                scene(time).render((self.new_renderer)())
+            })
+            .collect();
+
+        Ok(R::Output::blend(sub_frames))
+    }
+
+    /// Render `frame_count` frames of a scene into numbered files in `directory`.
+    pub fn write_files<P, S>(&self, directory: P, frame_count: usize, scene: S) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+        S: Fn(f32) -> Canvas,
+    {
+        for index in self.start_index..self.start_index + frame_count {
+            self.render_frame(&scene, index)?
+                .write_frame(&self.frame_path(&directory, index))?;
+        }
+
+        Ok(())
+    }
+
+    /// Render each [Canvas] snapshot, in order, into numbered files in `directory`.
+    ///
+    /// Snapshots are fixed in time, so motion blur is not applied.
+    pub fn write_canvases<'a, P, I>(&self, directory: P, canvases: I) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = &'a Canvas>,
+    {
+        for (index, canvas) in (self.start_index..).zip(canvases) {
+            canvas
+                .render((self.new_renderer)())
+                .write_frame(&self.frame_path(&directory, index))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<F, R> FrameSequence<F>
+where
+    F: Fn() -> R,
+    R: Renderer<Output = RgbaImage>,
+{
+    /// Render `frame_count` frames of a scene and write their raw RGBA bytes, one after another, to `writer`.
+    ///
+    /// This is the format expected by most encoders' raw video input (for example, `ffmpeg -f rawvideo -pix_fmt rgba`).
+    pub fn write_raw<W, S>(&self, mut writer: W, frame_count: usize, scene: S) -> io::Result<W>
+    where
+        W: Write,
+        S: Fn(f32) -> Canvas,
+    {
+        for index in self.start_index..self.start_index + frame_count {
+            writer.write_all(self.render_frame(&scene, index)?.as_raw())?;
+        }
+
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+/// The output of a [Renderer] that can be written as a frame of a [FrameSequence].
+pub trait FrameOutput: Sized {
+    /// The file extension used for frames of this type.
+    const EXTENSION: &'static str;
+
+    /// Write a single frame to a file.
+    fn write_frame(&self, path: &Path) -> io::Result<()>;
+
+    /// Combine several sub-frames into a single frame for motion blur.
+    fn blend(sub_frames: Vec<Self>) -> Self;
+}
+
+impl FrameOutput for RgbaImage {
+    const EXTENSION: &'static str = "png";
+
+    fn write_frame(&self, path: &Path) -> io::Result<()> {
+        self.save(path).map_err(io::Error::other)
+    }
+
+    /// Averages the sub-frames, weighting each color by its alpha so translucent edges don't darken.
+    fn blend(mut sub_frames: Vec<Self>) -> Self {
+        if sub_frames.len() == 1 {
+            return sub_frames.pop().unwrap();
+        }
+
+        let count = sub_frames.len() as f32;
+        let (width, height) = sub_frames[0].dimensions();
+        let mut image = RgbaImage::new(width, height);
+
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            let mut sum = Vec4::ZERO;
+            for sub_frame in &sub_frames {
+                let Rgba([r, g, b, a]) = *sub_frame.get_pixel(x, y);
+                let a = a as f32 / 255.0;
+                sum += Vec4::new(r as f32 * a, g as f32 * a, b as f32 * a, a);
+            }
+
+            let color = if sum.w > 0.0 {
+                sum.truncate() / sum.w
+            } else {
+                Vec3::ZERO
+            };
+            *pixel = Rgba([
+                color.x.round() as u8,
+                color.y.round() as u8,
+                color.z.round() as u8,
+                (sum.w / count * 255.0).round() as u8,
+            ]);
+        }
+
+        image
+    }
+}
+
+impl FrameOutput for String {
+    const EXTENSION: &'static str = "svg";
+
+    fn write_frame(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self)
+    }
+
+    /// Vector documents cannot be averaged, so the middle sub-frame is used.
+    fn blend(mut sub_frames: Vec<Self>) -> Self {
+        let middle = sub_frames.len() / 2;
+        sub_frames.swap_remove(middle)
+    }
+}
+
+pub(crate) fn png_error(error: png::EncodingError) -> ImageError {
+    ImageError::Encoding(EncodingError::new(
+        ImageFormatHint::Exact(ImageFormat::Png),
+        error,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, UVec2};
+    use image::{codecs::gif::GifDecoder, AnimationDecoder};
+
+    /// Verify that every frame survives a round trip through GIF.
+    #[test]
+    fn gif_round_trip() {
+        let animation = Animation::from_fn(
+            4,
+            |t| {
+                let mut canvas = Canvas::default();
+                canvas.draw_circle((t, 0.0), 0.5, None, Some(Color::white()));
+                canvas
+            },
+            &SkiaRenderer::new(UVec2::splat(16), Some(Color::black()), true, true),
+        );
+
+        let mut gif = Vec::new();
+        animation
+            .encode_gif(&mut gif, &AnimationOptions::default())
+            .unwrap();
+
+        let frames = GifDecoder::new(gif.as_slice())
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0].delay(), Delay::from_numer_denom_ms(40, 1));
+    }
+
+    /// Verify that motion blur averages the sub-frames of a moving shape, and that frames need a positive frame rate.
+    #[test]
+    fn motion_blur_averages_sub_frames() {
+        // A square that covers the left pixel for the first half of each frame and the right pixel for the second half.
+        let scene = |t: f32| {
+            let mut canvas = Canvas::default();
+            let x = if t.fract() < 0.5 { -1.0 } else { 0.0 };
+            canvas.draw_rect((x, -1.0), (x + 1.0, 1.0), None, Some(Color::white()));
+            canvas
+        };
+
+        let sequence = FrameSequence::new(
+            || SkiaRenderer::new(UVec2::new(2, 1), Some(Color::black()), false, false),
+            1.0,
+        )
+        .with_motion_blur(2);
+
+        let raw = sequence.write_raw(Vec::new(), 2, scene).unwrap();
+
+        assert_eq!(raw.len(), 2 * 2 * 4);
+        assert_eq!(&raw[..8], &[128, 128, 128, 255, 128, 128, 128, 255]);
+
+        // A translucent pixel fading out keeps its color, rather than darkening towards the empty one.
+        let blended = RgbaImage::blend(vec![
+            RgbaImage::from_raw(1, 1, vec![255, 0, 0, 128]).unwrap(),
+            RgbaImage::new(1, 1),
+        ]);
+        assert_eq!(blended.as_raw(), &[255, 0, 0, 64]);
+
+        let stopped = FrameSequence::new(
+            || SkiaRenderer::new(UVec2::new(2, 1), None, false, false),
+            0.0,
+        );
+        let error = stopped.write_raw(Vec::new(), 1, scene).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    /// Verify that frames are named deterministically from the start index.
+    #[test]
+    fn frame_paths_are_numbered() {
+        let sequence = FrameSequence::new(
+            || SkiaRenderer::new(UVec2::splat(1), None, false, false),
+            30.0,
+        )
+        .with_start_index(7);
+
+        assert_eq!(
+            sequence.frame_path("out", 7),
+            Path::new("out").join("frame_00007.png")
+        );
+        assert_eq!(sequence.frame_time(15), 0.5);
+    }
+}
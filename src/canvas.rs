@@ -1,7 +1,19 @@
-use std::f32::consts::PI;
-
-use crate::{color::Color, PathBuilder};
-use glam::{Mat2, Vec2};
+use std::{
+    error::Error,
+    f32::consts::PI,
+    fmt::{self, Display, Formatter},
+    io,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use crate::export::{ExportError, ExportOptions, ExportRegistry};
+use crate::{
+    color::Color, Markers, PathBuilder, Pattern, PatternTile, RepeatMode, Simplification,
+    SimplifyReport, Text,
+};
+use glam::{Affine2, Mat2, UVec2, Vec2};
+use image::{ImageError, RgbaImage};
 
 /// A polygonal shape with a stroke and fill.
 ///
@@ -15,9 +27,27 @@ pub struct Shape {
     pub stroke: Option<Stroke>,
     /// The area filled inside the points.
     pub fill: Option<Color>,
+    /// A pattern painted inside the points, on top of [fill](Self::fill).
+    pub pattern: Option<Pattern>,
+    /// An optional identifier, used by renderers to label and match shapes.
+    pub id: Option<String>,
+    /// Arrowheads and other markers along the stroke.
+    pub markers: Option<Markers>,
 }
 
 impl Shape {
+    /// A shape without any points, to be filled in later.
+    fn empty() -> Self {
+        Self {
+            points: Vec::new(),
+            stroke: None,
+            fill: None,
+            pattern: None,
+            id: None,
+            markers: None,
+        }
+    }
+
     /// Checks if a shape is a polygon, otherwise it is a polyline.
     pub fn is_polygon(&self) -> bool {
         if self.points.len() < 3 {
@@ -65,19 +95,223 @@ pub enum LineEnd {
     Round,
 }
 
+/// Information about a [Canvas], given to renderers before anything is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneInfo {
+    /// The number of shapes that will be rendered.
+    pub shape_count: usize,
+    /// The transform from World Space to Camera Space.
+    pub camera_transform: Affine2,
+    /// The zoom of the camera.
+    pub zoom: f32,
+    /// The bounding box of every shape in Camera Space, as `(min, max)`, or `None` if there are no shapes.
+    pub bounds: Option<(Vec2, Vec2)>,
+}
+
+/// The region of Camera Space a renderer draws, used by [Canvas] to skip what it can't see.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// The corner of the region with the smallest coordinates.
+    pub min: Vec2,
+    /// The corner of the region with the largest coordinates.
+    pub max: Vec2,
+    /// How many pixels a single unit of Camera Space covers, if the renderer works in pixels.
+    pub resolution: Option<f32>,
+}
+
 /// A renderer for [Canvas].
 ///
 /// If you want to implement your own rendering backend,
 /// reference either [SkiaRenderer](crate::renderers::SkiaRenderer) or [SvgRenderer](crate::renderers::SvgRenderer).
+///
+/// Only [render](Self::render) and [finalize](Self::finalize) are required.
+/// The other hooks describe the structure of the scene, and do nothing by default.
 pub trait Renderer {
     /// The intended format the renderer will output.
     type Output;
+    /// Called once, before anything else.
+    fn begin(&mut self, _info: &SceneInfo) {}
+    /// Get the region of Camera Space the renderer draws.
+    ///
+    /// Shapes entirely outside of it are skipped. By default, there is no limit.
+    /// Renderers whose output can be re-framed afterwards, like [SvgRenderer](crate::renderers::SvgRenderer), shouldn't report one.
+    fn viewport(&self) -> Option<Viewport> {
+        None
+    }
     /// Render a shape. Provided coordinates will be in Camera Space (from the perspective of the camera).
     fn render(&mut self, shape: &Shape);
+    /// Start a group of shapes, which lasts until the matching [end_group](Self::end_group).
+    fn begin_group(&mut self, _id: Option<&str>) {}
+    /// End the most recently started group.
+    fn end_group(&mut self) {}
+    /// Clip everything drawn afterwards to the inside of a polygon in Camera Space, or stop clipping if `None`.
+    ///
+    /// A clip lasts until it is replaced, or the group it was set in ends.
+    /// Inside a group, a clip only narrows the clip that was active when the group began.
+    fn set_clip(&mut self, _clip: Option<&[Vec2]>) {}
+    /// Draw an image. `transform` maps the pixels of the image (origin in the top left, y down) to Camera Space.
+    ///
+    /// By default, this renders a rectangle filled with the image as a [Pattern].
+    fn draw_image(&mut self, image: &Arc<RgbaImage>, transform: Affine2) {
+        self.render(&image_shape(image, transform));
+    }
+    /// Draw the [markers](Shape::markers) of a shape, right after the shape itself was rendered.
+    ///
+    /// By default, this renders each of the [marker_shapes](Shape::marker_shapes).
+    fn render_markers(&mut self, shape: &Shape) {
+        for marker in shape.marker_shapes() {
+            self.render(&marker);
+        }
+    }
     /// Finalize the render.
     fn finalize(self) -> Self::Output;
 }
 
+/// Build a rectangle filled with an image, for renderers that don't draw images themselves.
+fn image_shape(image: &Arc<RgbaImage>, transform: Affine2) -> Shape {
+    let size = Vec2::new(image.width() as f32, image.height() as f32);
+
+    // Pattern Space has y up, while the pixels of the image have y down.
+    let flip = Affine2::from_mat2_translation(
+        Mat2::from_diagonal(Vec2::new(1.0, -1.0)),
+        Vec2::new(0.0, size.y),
+    );
+
+    Shape {
+        points: [
+            Vec2::ZERO,
+            Vec2::new(size.x, 0.0),
+            size,
+            Vec2::new(0.0, size.y),
+            Vec2::ZERO,
+        ]
+        .iter()
+        .map(|point| transform.transform_point2(*point))
+        .collect(),
+        stroke: None,
+        fill: None,
+        pattern: Some(Pattern {
+            tile: PatternTile::Image(image.clone()),
+            size,
+            transform: transform * flip,
+            repeat: RepeatMode::Pad,
+        }),
+        id: None,
+        markers: None,
+    }
+}
+
+/// A renderer for [Canvas] that can fail.
+///
+/// Use it with [Canvas::try_render]. Rendering stops at the first error.
+pub trait TryRenderer {
+    /// The intended format the renderer will output.
+    type Output;
+    /// The error the renderer can fail with.
+    type Error;
+    /// Called once, before anything else. See [Renderer::begin].
+    fn try_begin(&mut self, _info: &SceneInfo) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    /// Get the region of Camera Space the renderer draws. See [Renderer::viewport].
+    fn try_viewport(&self) -> Option<Viewport> {
+        None
+    }
+    /// Render a shape. Provided coordinates will be in Camera Space (from the perspective of the camera).
+    fn try_render(&mut self, shape: &Shape) -> Result<(), Self::Error>;
+    /// Start a group of shapes. See [Renderer::begin_group].
+    fn try_begin_group(&mut self, _id: Option<&str>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    /// End the most recently started group. See [Renderer::end_group].
+    fn try_end_group(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    /// Clip everything drawn afterwards. See [Renderer::set_clip].
+    fn try_set_clip(&mut self, _clip: Option<&[Vec2]>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    /// Draw an image. See [Renderer::draw_image].
+    fn try_draw_image(
+        &mut self,
+        image: &Arc<RgbaImage>,
+        transform: Affine2,
+    ) -> Result<(), Self::Error> {
+        self.try_render(&image_shape(image, transform))
+    }
+    /// Draw the markers of a shape. See [Renderer::render_markers].
+    fn try_render_markers(&mut self, shape: &Shape) -> Result<(), Self::Error> {
+        for marker in shape.marker_shapes() {
+            self.try_render(&marker)?;
+        }
+        Ok(())
+    }
+    /// Finalize the render.
+    fn try_finalize(self) -> Result<Self::Output, Self::Error>;
+}
+
+/// An error from one of the built-in renderers.
+#[derive(Debug)]
+pub enum RenderError {
+    /// The output can't be created at the requested size, such as when it has no area.
+    InvalidSize(UVec2),
+    /// Writing the output failed.
+    Io(io::Error),
+    /// Encoding an image for the output failed, such as an image with no area.
+    Image(ImageError),
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::InvalidSize(size) => {
+                write!(f, "cannot render an image of size {}x{}", size.x, size.y)
+            }
+            RenderError::Io(error) => write!(f, "failed to write output: {}", error),
+            RenderError::Image(error) => write!(f, "failed to encode image: {}", error),
+        }
+    }
+}
+
+impl Error for RenderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RenderError::Io(error) => Some(error),
+            RenderError::Image(error) => Some(error),
+            RenderError::InvalidSize(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for RenderError {
+    fn from(error: io::Error) -> Self {
+        RenderError::Io(error)
+    }
+}
+
+impl From<ImageError> for RenderError {
+    fn from(error: ImageError) -> Self {
+        RenderError::Image(error)
+    }
+}
+
+/// A [Renderer] that collects the shapes it is given, in Camera Space.
+#[cfg_attr(not(feature = "svg_renderer"), allow(dead_code))]
+#[derive(Debug, Default)]
+pub(crate) struct ShapeCollector(pub Vec<Shape>);
+
+impl Renderer for ShapeCollector {
+    type Output = Vec<Shape>;
+
+    fn render(&mut self, shape: &Shape) {
+        self.0.push(shape.clone());
+    }
+
+    fn finalize(self) -> Self::Output {
+        self.0
+    }
+}
+
 /// A canvas that can be used with many backends.
 ///
 /// There are two 'spaces': `World Space` and `View Space`.
@@ -87,7 +321,7 @@ pub trait Renderer {
 /// This means that, by default, `View Space` and `World Space` are equal. Once the camera has been changed, any drawing will be from the perspective of `View Space` onto `World Space`.
 ///
 /// For example, a rectangle with corners at `(-1, -1)` and `(1, 1)` will be twice as large in World Space if it is drawn while the camera's `zoom` is at `0.5`.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Canvas {
     points_per_unit: usize,
     zoom: f32,
@@ -95,6 +329,114 @@ pub struct Canvas {
     to_camera_matrix: Mat2,
     to_world_matrix: Mat2,
     shapes: Vec<Shape>,
+    /// Structure that isn't a shape, along with the number of shapes drawn before it.
+    commands: Vec<(usize, Command)>,
+    culling: bool,
+    level_of_detail: Option<f32>,
+    cache: ShapeCache,
+}
+
+/// What [Canvas::render] remembers about each shape, so it doesn't have to work it out again.
+#[derive(Debug, Clone, Default)]
+struct CachedShape {
+    /// The bounds of the shape's points in World Space, as `(min, max)`.
+    bounds: Option<(Vec2, Vec2)>,
+    /// The shape's points with details smaller than a tolerance removed, along with that tolerance in World Space.
+    simplified: Option<(f32, Vec<Vec2>)>,
+}
+
+/// A [CachedShape] for each shape of a [Canvas].
+///
+/// The cache never changes what a canvas looks like, so it is left out of clones and comparisons.
+#[derive(Default)]
+struct ShapeCache(Mutex<Vec<CachedShape>>);
+
+impl ShapeCache {
+    /// Forget everything, after shapes were changed.
+    fn invalidate(&mut self) {
+        if let Ok(cache) = self.0.get_mut() {
+            cache.clear();
+        }
+    }
+}
+
+impl Clone for ShapeCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for ShapeCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for ShapeCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("ShapeCache")
+    }
+}
+
+/// A change to the structure of a [Canvas], in World Space.
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    BeginGroup(Option<String>),
+    EndGroup,
+    SetClip(Option<Vec<Vec2>>),
+    DrawImage(Arc<RgbaImage>, Affine2),
+}
+
+/// Get the transform from the pixels of an image to the rectangle with its top left corner at `top_left` that is `size` large.
+fn image_transform<P: Into<Vec2>>(image: &RgbaImage, top_left: P, size: P) -> Affine2 {
+    let size = size.into();
+
+    Affine2::from_mat2_translation(
+        Mat2::from_diagonal(Vec2::new(
+            size.x / image.width() as f32,
+            -size.y / image.height() as f32,
+        )),
+        top_left.into(),
+    )
+}
+
+/// Find the bounds of some points, as `(min, max)`.
+fn world_bounds(points: &[Vec2]) -> (Vec2, Vec2) {
+    points.iter().fold(
+        (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+        |(min, max), point| {
+            (
+                Vec2::select(point.cmplt(min), *point, min),
+                Vec2::select(point.cmpgt(max), *point, max),
+            )
+        },
+    )
+}
+
+/// Drop points that are closer than `tolerance` to the last point that was kept.
+///
+/// The first and last points are always kept, so closed shapes stay closed.
+fn drop_close_points(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    let (first, last) = match points {
+        [first, .., last] => (*first, *last),
+        _ => return points.to_vec(),
+    };
+
+    let mut kept = vec![first];
+    for point in &points[1..points.len() - 1] {
+        if kept[kept.len() - 1].distance(*point) >= tolerance {
+            kept.push(*point);
+        }
+    }
+    kept.push(last);
+
+    kept
+}
+
+/// A single step of rendering a [Canvas].
+enum Step<'a> {
+    Shape(&'a Shape),
+    Command(&'a Command),
 }
 
 impl Default for Canvas {
@@ -107,6 +449,10 @@ impl Default for Canvas {
             to_camera_matrix: Mat2::IDENTITY,
             to_world_matrix: Mat2::IDENTITY,
             shapes: Vec::new(),
+            commands: Vec::new(),
+            culling: true,
+            level_of_detail: None,
+            cache: ShapeCache::default(),
         }
     }
 }
@@ -123,26 +469,232 @@ impl Canvas {
             to_camera_matrix: Mat2::IDENTITY,
             to_world_matrix: Mat2::IDENTITY,
             shapes: Vec::new(),
+            commands: Vec::new(),
+            culling: true,
+            level_of_detail: None,
+            cache: ShapeCache::default(),
         }
     }
 
     /// Render the canvas using a renderer of your choice.
+    ///
+    /// Each shape is transformed into the same scratch [Shape] before it is given to the renderer,
+    /// so rendering doesn't allocate once the scratch is large enough for the biggest shape.
+    ///
+    /// Shapes outside of the renderer's [viewport](Renderer::viewport) are skipped,
+    /// unless culling is turned off with [set_culling](Self::set_culling).
     pub fn render<R: Renderer>(&self, mut renderer: R) -> R::Output {
-        for shape in &self.shapes {
-            let mut transformed_shape = shape.clone();
+        renderer.begin(&self.scene_info());
+
+        let viewport = renderer.viewport();
+        let mut cache = self.lock_cache();
+        let mut scratch = Shape::empty();
+        for (index, step) in self.steps() {
+            match step {
+                Step::Shape(shape) => {
+                    if self.prepare_shape(index, shape, viewport, &mut cache, &mut scratch) {
+                        renderer.render(&scratch);
+                        if scratch.markers.is_some() {
+                            renderer.render_markers(&scratch);
+                        }
+                    }
+                }
+                Step::Command(Command::BeginGroup(id)) => renderer.begin_group(id.as_deref()),
+                Step::Command(Command::EndGroup) => renderer.end_group(),
+                Step::Command(Command::SetClip(clip)) => {
+                    renderer.set_clip(self.clip_to_camera_space(clip).as_deref())
+                }
+                Step::Command(Command::DrawImage(image, transform)) => {
+                    renderer.draw_image(image, self.camera_transform() * *transform)
+                }
+            }
+        }
+
+        renderer.finalize()
+    }
+
+    /// Render the canvas using a renderer that can fail, stopping at the first error.
+    pub fn try_render<R: TryRenderer>(&self, mut renderer: R) -> Result<R::Output, R::Error> {
+        renderer.try_begin(&self.scene_info())?;
+
+        let viewport = renderer.try_viewport();
+        let mut cache = self.lock_cache();
+        let mut scratch = Shape::empty();
+        for (index, step) in self.steps() {
+            match step {
+                Step::Shape(shape) => {
+                    if self.prepare_shape(index, shape, viewport, &mut cache, &mut scratch) {
+                        renderer.try_render(&scratch)?;
+                        if scratch.markers.is_some() {
+                            renderer.try_render_markers(&scratch)?;
+                        }
+                    }
+                }
+                Step::Command(Command::BeginGroup(id)) => {
+                    renderer.try_begin_group(id.as_deref())?
+                }
+                Step::Command(Command::EndGroup) => renderer.try_end_group()?,
+                Step::Command(Command::SetClip(clip)) => {
+                    renderer.try_set_clip(self.clip_to_camera_space(clip).as_deref())?
+                }
+                Step::Command(Command::DrawImage(image, transform)) => {
+                    renderer.try_draw_image(image, self.camera_transform() * *transform)?
+                }
+            }
+        }
+
+        renderer.try_finalize()
+    }
+
+    /// Get information about the canvas, from the perspective of the camera.
+    pub fn scene_info(&self) -> SceneInfo {
+        let bounds = self
+            .shapes
+            .iter()
+            .flat_map(|shape| shape.points.iter())
+            .map(|point| self.to_camera_space(*point))
+            .fold(None, |bounds: Option<(Vec2, Vec2)>, point| match bounds {
+                Some((min, max)) => Some((min.min(point), max.max(point))),
+                None => Some((point, point)),
+            });
+
+        SceneInfo {
+            shape_count: self.shapes.len(),
+            camera_transform: self.camera_transform(),
+            zoom: self.zoom,
+            bounds,
+        }
+    }
+
+    /// Interleave the shapes and commands, in the order they were drawn, along with the index of the next shape.
+    fn steps(&self) -> impl Iterator<Item = (usize, Step<'_>)> {
+        let mut shapes = self.shapes.iter().enumerate().peekable();
+        let mut commands = self.commands.iter().peekable();
+
+        std::iter::from_fn(move || {
+            let next_shape = shapes.peek().map_or(usize::MAX, |(index, _)| *index);
 
-            for point in transformed_shape.points.iter_mut() {
-                *point = self.to_camera_space(*point);
+            match commands.next_if(|(at, _)| *at <= next_shape) {
+                Some((_, command)) => Some((next_shape, Step::Command(command))),
+                None => shapes
+                    .next()
+                    .map(|(index, shape)| (index, Step::Shape(shape))),
             }
+        })
+    }
+
+    /// Lock the cache, making room for every shape.
+    ///
+    /// Returns `None` if another thread is rendering the canvas, in which case nothing is cached.
+    fn lock_cache(&self) -> Option<std::sync::MutexGuard<'_, Vec<CachedShape>>> {
+        let mut cache = self.cache.0.try_lock().ok()?;
+        cache.resize_with(self.shapes.len(), CachedShape::default);
+        Some(cache)
+    }
+
+    /// Transform a shape into `scratch`, returning `false` if it can't be seen through `viewport`.
+    fn prepare_shape(
+        &self,
+        index: usize,
+        shape: &Shape,
+        viewport: Option<Viewport>,
+        cache: &mut Option<std::sync::MutexGuard<'_, Vec<CachedShape>>>,
+        scratch: &mut Shape,
+    ) -> bool {
+        let viewport = match viewport {
+            Some(viewport) => viewport,
+            None => {
+                self.shape_to_camera_space(shape, &shape.points, scratch);
+                return true;
+            }
+        };
+        let mut uncached = CachedShape::default();
+        let cached = match cache {
+            Some(cache) => &mut cache[index],
+            None => &mut uncached,
+        };
+
+        if self.culling {
+            let bounds = *cached
+                .bounds
+                .get_or_insert_with(|| world_bounds(&shape.points));
+
+            // Leave room for the stroke's joins and markers, and a pixel of antialiasing.
+            let reach = shape
+                .markers
+                .as_ref()
+                .map_or(2.0, |markers| markers.extent().max(2.0));
+            let margin = shape
+                .stroke
+                .map_or(0.0, |stroke| stroke.width * self.zoom * reach)
+                + viewport
+                    .resolution
+                    .map_or(0.0, |resolution| 1.0 / resolution);
+            let (min, max) = self.bounds_to_camera_space(bounds);
+
+            if (min - margin).cmpgt(viewport.max).any() || (max + margin).cmplt(viewport.min).any()
+            {
+                return false;
+            }
+        }
 
-            if let Some(stroke) = &mut transformed_shape.stroke {
-                stroke.width *= self.zoom;
+        let tolerance = match (self.level_of_detail, viewport.resolution) {
+            (Some(pixels), Some(resolution)) => pixels / resolution / self.zoom,
+            _ => {
+                self.shape_to_camera_space(shape, &shape.points, scratch);
+                return true;
             }
+        };
 
-            renderer.render(&transformed_shape);
+        if cached.simplified.as_ref().map(|(at, _)| *at) != Some(tolerance) {
+            cached.simplified = Some((tolerance, drop_close_points(&shape.points, tolerance)));
         }
 
-        renderer.finalize()
+        if let Some((_, points)) = &cached.simplified {
+            self.shape_to_camera_space(shape, points, scratch);
+        }
+        true
+    }
+
+    /// Transform a box in World Space into the box around it in Camera Space.
+    fn bounds_to_camera_space(&self, (min, max): (Vec2, Vec2)) -> (Vec2, Vec2) {
+        let corners = [min, Vec2::new(min.x, max.y), max, Vec2::new(max.x, min.y)]
+            .map(|corner| self.to_camera_space(corner));
+
+        corners[1..]
+            .iter()
+            .fold((corners[0], corners[0]), |(min, max), corner| {
+                (min.min(*corner), max.max(*corner))
+            })
+    }
+
+    fn clip_to_camera_space(&self, clip: &Option<Vec<Vec2>>) -> Option<Vec<Vec2>> {
+        clip.as_ref().map(|clip| {
+            clip.iter()
+                .map(|point| self.to_camera_space(*point))
+                .collect()
+        })
+    }
+
+    /// Copy a shape, with its points replaced by `points`, into Camera Space, reusing the allocations of `scratch`.
+    fn shape_to_camera_space(&self, shape: &Shape, points: &[Vec2], scratch: &mut Shape) {
+        scratch.points.clear();
+        scratch
+            .points
+            .extend(points.iter().map(|point| self.to_camera_space(*point)));
+
+        scratch.stroke = shape.stroke.map(|mut stroke| {
+            stroke.width *= self.zoom;
+            stroke
+        });
+        scratch.fill = shape.fill;
+        scratch.id.clone_from(&shape.id);
+        scratch.markers.clone_from(&shape.markers);
+
+        scratch.pattern.clone_from(&shape.pattern);
+        if let Some(pattern) = &mut scratch.pattern {
+            pattern.transform = self.camera_transform() * pattern.transform;
+        }
     }
 
     /// Returns a [Vec] of all the [Shapes](Shape) drawn on the canvas.
@@ -150,6 +702,87 @@ impl Canvas {
         self.shapes
     }
 
+    /// Render the canvas and write it to a file, picking the renderer and encoder by the path's extension.
+    ///
+    /// This is a shortcut for [export_with](Self::export_with) and the [default registry](crate::export::ExportRegistry),
+    /// which supports every format enabled by the crate's features.
+    pub fn export<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &ExportOptions,
+    ) -> Result<(), ExportError> {
+        self.export_with(&ExportRegistry::default(), path, options)
+    }
+
+    /// Render the canvas and write it to a file, picking the exporter from `registry` by the path's extension.
+    ///
+    /// Use this to reach formats you have [registered](crate::export::ExportRegistry::register) yourself.
+    pub fn export_with<P: AsRef<Path>>(
+        &self,
+        registry: &ExportRegistry,
+        path: P,
+        options: &ExportOptions,
+    ) -> Result<(), ExportError> {
+        registry.export(self, path, options)
+    }
+
+    /// Start a group of shapes, which lasts until the matching [end_group](Self::end_group).
+    ///
+    /// Groups don't change how shapes are drawn, but renderers may use them to structure their output.
+    pub fn begin_group<S: Into<String>>(&mut self, id: Option<S>) {
+        self.push_command(Command::BeginGroup(id.map(Into::into)));
+    }
+
+    /// End the most recently started group.
+    pub fn end_group(&mut self) {
+        self.push_command(Command::EndGroup);
+    }
+
+    /// Clip everything drawn afterwards to the inside of a polygon, projected from the camera.
+    ///
+    /// The clip lasts until it is replaced or cleared, or the group it was set in ends.
+    /// Inside a group, it only narrows the clip that was active when the group began.
+    pub fn set_clip<C: Into<Vec<Vec2>>>(&mut self, points: C) {
+        let points = points
+            .into()
+            .into_iter()
+            .map(|point| self.to_world_space(point))
+            .collect();
+        self.push_command(Command::SetClip(Some(points)));
+    }
+
+    /// Clip everything drawn afterwards to the inside of a polygon in World Space.
+    ///
+    /// See [set_clip](Self::set_clip).
+    pub fn set_clip_absolute<C: Into<Vec<Vec2>>>(&mut self, points: C) {
+        self.push_command(Command::SetClip(Some(points.into())));
+    }
+
+    /// Stop clipping.
+    pub fn clear_clip(&mut self) {
+        self.push_command(Command::SetClip(None));
+    }
+
+    /// Draw an image onto the canvas, projected from the camera.
+    ///
+    /// The image is stretched to fill the rectangle with its top left corner at `top_left` that is `size` large.
+    pub fn draw_image<P: Into<Vec2>>(&mut self, image: RgbaImage, top_left: P, size: P) {
+        let transform = self.camera_transform().inverse() * image_transform(&image, top_left, size);
+        self.push_command(Command::DrawImage(Arc::new(image), transform));
+    }
+
+    /// Draw an image directly onto the canvas.
+    ///
+    /// See [draw_image](Self::draw_image).
+    pub fn draw_image_absolute<P: Into<Vec2>>(&mut self, image: RgbaImage, top_left: P, size: P) {
+        let transform = image_transform(&image, top_left, size);
+        self.push_command(Command::DrawImage(Arc::new(image), transform));
+    }
+
+    fn push_command(&mut self, command: Command) {
+        self.commands.push((self.shapes.len(), command));
+    }
+
     /// Returns a slice of all the [Shapes](Shape) drawn on the canvas.
     pub fn as_raw(&self) -> &[Shape] {
         self.shapes.as_slice()
@@ -157,9 +790,59 @@ impl Canvas {
 
     /// Returns a mutable slice of all the [Shapes](Shape) drawn on the canvas.
     pub fn as_raw_mut(&mut self) -> &mut [Shape] {
+        self.cache.invalidate();
         self.shapes.as_mut_slice()
     }
 
+    /// Simplify every shape on the canvas, with `tolerance` measured in Camera Space at the current zoom.
+    ///
+    /// See [Shape::simplify].
+    pub fn simplify(&mut self, method: Simplification, tolerance: f32) -> SimplifyReport {
+        self.simplify_absolute(method, tolerance / self.zoom)
+    }
+
+    /// Simplify every shape on the canvas, with `tolerance` measured in World Space.
+    ///
+    /// See [Shape::simplify].
+    pub fn simplify_absolute(&mut self, method: Simplification, tolerance: f32) -> SimplifyReport {
+        self.as_raw_mut()
+            .iter_mut()
+            .fold(SimplifyReport::default(), |report, shape| {
+                report + shape.simplify(method, tolerance)
+            })
+    }
+
+    /// Choose whether to skip shapes that are outside of the renderer's [viewport](Renderer::viewport). On by default.
+    pub fn set_culling(&mut self, culling: bool) {
+        self.culling = culling;
+    }
+
+    /// Drop points that are closer than `tolerance` pixels to the previous point while rendering, or `None` to keep every point.
+    ///
+    /// Only renderers that report a [resolution](Viewport::resolution) are affected.
+    /// The simplified points are remembered, so rendering again at the same scale is cheap.
+    pub fn set_level_of_detail(&mut self, tolerance: Option<f32>) {
+        self.level_of_detail = tolerance;
+    }
+
+    /// Assign an id to the most recently drawn shape.
+    ///
+    /// Helpers that draw several shapes, like [draw_path](Self::draw_path), only label the last one.
+    pub fn set_last_shape_id<S: Into<String>>(&mut self, id: S) {
+        if let Some(shape) = self.shapes.last_mut() {
+            shape.id = Some(id.into());
+        }
+    }
+
+    /// Set the [Markers] of the most recently drawn shape, like an arrowhead at the end of a line.
+    ///
+    /// Markers are only drawn on shapes with a stroke, and scale with its width.
+    pub fn set_last_shape_markers(&mut self, markers: Markers) {
+        if let Some(shape) = self.shapes.last_mut() {
+            shape.markers = Some(markers);
+        }
+    }
+
     /// Rotate the camera counter-clockwise.
     pub fn rotate_camera(&mut self, radians: f32) {
         let rotate_mat = Mat2::from_angle(radians);
@@ -182,9 +865,11 @@ impl Canvas {
         self.zoom *= zoom;
     }
 
-    /// Clears the canvas
+    /// Clears the canvas, including any groups, clips and images.
     pub fn clear(&mut self) {
         self.shapes.clear();
+        self.commands.clear();
+        self.cache.invalidate();
     }
 
     /// Draw a shape onto the canvas, projected from the camera.
@@ -219,6 +904,9 @@ impl Canvas {
             points,
             stroke,
             fill,
+            pattern: None,
+            id: None,
+            markers: None,
         })
     }
 
@@ -248,9 +936,48 @@ impl Canvas {
             points,
             stroke,
             fill,
+            pattern: None,
+            id: None,
+            markers: None,
         })
     }
 
+    /// Draw a shape filled with a [Pattern] onto the canvas, projected from the camera.
+    ///
+    /// The pattern's transform is projected from the camera as well.
+    /// If a shape has one or fewer points, it will be discarded.
+    pub fn draw_shape_with_pattern<C: Into<Vec<Vec2>>>(
+        &mut self,
+        points: C,
+        stroke: Option<Stroke>,
+        mut pattern: Pattern,
+    ) {
+        let shape_count = self.shapes.len();
+        self.draw_shape(points, stroke, None);
+
+        pattern.transform = self.camera_transform().inverse() * pattern.transform;
+        if let Some(shape) = self.shapes.get_mut(shape_count) {
+            shape.pattern = Some(pattern);
+        }
+    }
+
+    /// Draw a shape filled with a [Pattern] directly onto the canvas.
+    ///
+    /// If a shape has one or fewer points, it will be discarded.
+    pub fn draw_shape_with_pattern_absolute<C: Into<Vec<Vec2>>>(
+        &mut self,
+        points: C,
+        stroke: Option<Stroke>,
+        pattern: Pattern,
+    ) {
+        let shape_count = self.shapes.len();
+        self.draw_shape_absolute(points, stroke, None);
+
+        if let Some(shape) = self.shapes.get_mut(shape_count) {
+            shape.pattern = Some(pattern);
+        }
+    }
+
     /// Draw a rectangle onto the canvas, projected from the camera.
     pub fn draw_rect<P: Into<Vec2>>(
         &mut self,
@@ -581,6 +1308,20 @@ impl Canvas {
         self.draw_shape_absolute(points, Some(stroke), None);
     }
 
+    /// Draw a line of [Text] onto the canvas, projected from the camera.
+    pub fn draw_text(&mut self, text: &Text, stroke: Stroke) {
+        for points in text.strokes() {
+            self.draw_polyline(points, stroke);
+        }
+    }
+
+    /// Draw a line of [Text] directly onto the canvas.
+    pub fn draw_text_absolute(&mut self, text: &Text, stroke: Stroke) {
+        for points in text.strokes() {
+            self.draw_polyline_absolute(points, stroke);
+        }
+    }
+
     /// Draw a solid shape made of several sides onto the canvas, projected from the camera.
     pub fn draw_polygon<C: Into<Vec<Vec2>>>(&mut self, points: C, fill: Color) {
         self.draw_shape(points, None, Some(fill));
@@ -603,6 +1344,14 @@ impl Canvas {
         self.to_world_matrix.mul_vec2(point.into()) + self.translation
     }
 
+    /// Get the transform from world space to camera space as a matrix.
+    pub(crate) fn camera_transform(&self) -> Affine2 {
+        Affine2::from_mat2_translation(
+            self.to_camera_matrix,
+            -self.to_camera_matrix.mul_vec2(self.translation),
+        )
+    }
+
     /// Get the canvas' points per unit.
     ///
     /// This is essentially how detailed it will generate certain kinds of geometry (bezier curves, circles).
@@ -819,4 +1568,163 @@ mod tests {
             Vec2::new(0.5, 0.5),
         );
     }
+
+    /// Records the hooks it is called with.
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl Renderer for Recorder {
+        type Output = Vec<String>;
+
+        fn begin(&mut self, info: &SceneInfo) {
+            self.0.push(format!("begin {}", info.shape_count));
+        }
+
+        fn render(&mut self, shape: &Shape) {
+            self.0.push(format!("shape {}", shape.points.len()));
+        }
+
+        fn begin_group(&mut self, id: Option<&str>) {
+            self.0.push(format!("group {:?}", id));
+        }
+
+        fn end_group(&mut self) {
+            self.0.push("end".to_string());
+        }
+
+        fn set_clip(&mut self, clip: Option<&[Vec2]>) {
+            self.0
+                .push(format!("clip {:?}", clip.map(|clip| clip.len())));
+        }
+
+        fn finalize(self) -> Self::Output {
+            self.0
+        }
+    }
+
+    /// Verify that groups, clips and images reach the renderer in the order they were drawn, in Camera Space,
+    /// and that clearing the canvas removes them.
+    #[test]
+    fn commands_are_ordered() {
+        let mut canvas = Canvas::default();
+        canvas.zoom_camera(2.0);
+
+        canvas.begin_group(Some("first"));
+        canvas.draw_line((0.0, 0.0), (1.0, 1.0), None, None);
+        canvas.set_clip(vec![Vec2::ZERO, Vec2::X, Vec2::Y]);
+        canvas.draw_triangle((0.0, 0.0), (1.0, 0.0), (0.0, 1.0), None, None);
+        canvas.end_group();
+        canvas.clear_clip();
+        canvas.draw_image_absolute(RgbaImage::new(2, 1), (-1.0, 1.0), (2.0, 2.0));
+
+        assert_eq!(
+            canvas.render(Recorder::default()),
+            [
+                "begin 2",
+                "group Some(\"first\")",
+                "shape 2",
+                "clip Some(3)",
+                "shape 3",
+                "end",
+                "clip None",
+                "shape 5",
+            ]
+        );
+
+        let info = canvas.scene_info();
+        assert_eq!(info.zoom, 2.0);
+        let (min, max) = info.bounds.unwrap();
+        assert_vec2_eq(min, Vec2::new(0.0, 0.0));
+        assert_vec2_eq(max, Vec2::new(1.0, 1.0));
+
+        // The bounds fit the shapes exactly, even when the camera is rotated.
+        let mut rotated = Canvas::default();
+        rotated.draw_line((0.0, 0.0), (1.0, 1.0), None, None);
+        rotated.rotate_camera(PI / 4.0);
+        let (min, max) = rotated.scene_info().bounds.unwrap();
+        assert!((max.x - min.x).abs() < 1e-5);
+
+        // The image falls back to a rectangle with a pattern, covering where it was drawn.
+        let image = match canvas.commands.last() {
+            Some((_, Command::DrawImage(image, transform))) => {
+                image_shape(image, canvas.camera_transform() * *transform)
+            }
+            _ => unreachable!(),
+        };
+        assert_vec2_eq(image.points[0], Vec2::new(-2.0, 2.0));
+        assert_vec2_eq(image.points[2], Vec2::new(2.0, -2.0));
+
+        canvas.clear();
+        assert_eq!(canvas.render(Recorder::default()), ["begin 0"]);
+    }
+
+    /// Collects the shapes it is given, and draws a 200 by 100 pixel region of Camera Space.
+    struct Collector(Vec<Shape>);
+
+    impl Renderer for Collector {
+        type Output = Vec<Shape>;
+
+        fn viewport(&self) -> Option<Viewport> {
+            Some(Viewport {
+                min: Vec2::new(-2.0, -1.0),
+                max: Vec2::new(2.0, 1.0),
+                resolution: Some(50.0),
+            })
+        }
+
+        fn render(&mut self, shape: &Shape) {
+            self.0.push(shape.clone());
+        }
+
+        fn finalize(self) -> Self::Output {
+            self.0
+        }
+    }
+
+    /// Verify that shapes outside of the viewport are skipped, unless their stroke reaches into it.
+    #[test]
+    fn culls_outside_viewport() {
+        let mut canvas = Canvas::default();
+        canvas.move_camera((10.0, 0.0));
+
+        canvas.draw_line((-1.0, 0.0), (1.0, 0.0), None, None);
+        canvas.draw_line((3.0, 0.0), (4.0, 0.0), None, None);
+        canvas.draw_line(
+            (2.5, 0.0),
+            (4.0, 0.0),
+            Some(Stroke::new(Color::black(), 0.6, LineEnd::Butt)),
+            None,
+        );
+
+        assert_eq!(canvas.render(Collector(Vec::new())).len(), 2);
+
+        canvas.set_culling(false);
+        assert_eq!(canvas.render(Collector(Vec::new())).len(), 3);
+    }
+
+    /// Verify that level of detail drops subpixel points, keeps shapes closed, and is cached.
+    #[test]
+    fn level_of_detail() {
+        let mut canvas = Canvas::default();
+        canvas.draw_circle((0.0, 0.0), 0.5, None, None);
+        canvas.set_level_of_detail(Some(4.0));
+
+        let shapes = canvas.render(Collector(Vec::new()));
+        let circle = &shapes[0];
+        assert!(circle.points.len() < canvas.as_raw()[0].points.len() / 4);
+        assert!(circle.is_polygon());
+        for pair in circle.points[..circle.points.len() - 1].windows(2) {
+            assert!(pair[0].distance(pair[1]) * 50.0 >= 4.0);
+        }
+
+        // Rendering again at the same scale reuses the simplified points.
+        let cached = canvas.cache.0.lock().unwrap()[0].simplified.clone();
+        assert_eq!(canvas.render(Collector(Vec::new())), shapes);
+        assert_eq!(canvas.cache.0.lock().unwrap()[0].simplified, cached);
+
+        // Changing the shapes forgets them.
+        canvas.as_raw_mut()[0].points.truncate(10);
+        assert!(canvas.cache.0.lock().unwrap().is_empty());
+        assert_eq!(canvas.render(Collector(Vec::new()))[0].points.len(), 2);
+    }
 }
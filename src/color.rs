@@ -1,11 +1,16 @@
 use std::{
-    num::ParseIntError,
+    error::Error,
+    f32::consts::TAU,
+    fmt::{self, Display, Formatter},
     ops::{Add, Div, Mul, Rem, Sub},
+    str::FromStr,
 };
 
-use glam::Vec4;
+use glam::{Vec3, Vec4};
 use image::{Rgb, Rgba};
 
+use crate::palette::named_color;
+
 /// A structure that represents an RGBA color. All values are [f32] from 0.0..=1.0.
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub struct Color {
@@ -141,47 +146,322 @@ impl Color {
         self
     }
 
-    /// Creates a [Color] from HSV values.
+    /// Creates a [Color] from HSV values and an alpha.
     ///
-    /// Note: all inputs must be of range 0..1.
+    /// `hue` is measured in turns, so it wraps around outside of `0.0..1.0`.
+    /// `saturation` and `value` are clamped to `0.0..=1.0`.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+
+        let chroma = saturation * value;
+        Self::from_hue_chroma(hue, chroma, value - chroma, alpha)
+    }
+
+    /// Get the HSV values of the color, as `(hue, saturation, value, alpha)`.
     ///
-    /// Implementation from [Rosetta Code](https://rosettacode.org/wiki/Color_wheel#Rust).
-    #[allow(clippy::many_single_char_names)]
-    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
-        if hue + saturation + value > 3.0 {
-            panic!("All HSV values must be below 1.0.");
-        }
+    /// `hue` is measured in turns, from `0.0..1.0`. The hue of a gray is `0.0`.
+    pub fn to_hsv(&self) -> (f32, f32, f32, f32) {
+        let max = self.r().max(self.g()).max(self.b());
+        let min = self.r().min(self.g()).min(self.b());
+        let chroma = max - min;
+
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+
+        (self.hue(max, chroma), saturation, max, self.a())
+    }
+
+    /// Creates a [Color] from HSL values and an alpha.
+    ///
+    /// `hue` is measured in turns, so it wraps around outside of `0.0..1.0`.
+    /// `saturation` and `lightness` are clamped to `0.0..=1.0`.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        Self::from_hue_chroma(hue, chroma, lightness - chroma / 2.0, alpha)
+    }
+
+    /// Get the HSL values of the color, as `(hue, saturation, lightness, alpha)`.
+    ///
+    /// `hue` is measured in turns, from `0.0..1.0`. The hue of a gray is `0.0`.
+    pub fn to_hsl(&self) -> (f32, f32, f32, f32) {
+        let max = self.r().max(self.g()).max(self.b());
+        let min = self.r().min(self.g()).min(self.b());
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if chroma > 0.0 {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+
+        (self.hue(max, chroma), saturation, lightness, self.a())
+    }
+
+    /// Creates a [Color] from linear light RGB values, in the sRGB primaries, and an alpha.
+    ///
+    /// Alpha is not affected by the transfer function.
+    pub fn from_linear_srgb(r: f32, g: f32, b: f32, alpha: f32) -> Self {
+        Self::new(
+            linear_to_srgb(r),
+            linear_to_srgb(g),
+            linear_to_srgb(b),
+            alpha,
+        )
+    }
+
+    /// Get the color in linear light, as `(r, g, b, alpha)`.
+    ///
+    /// This undoes the sRGB transfer function, so values are proportional to physical light.
+    pub fn to_linear_srgb(&self) -> (f32, f32, f32, f32) {
+        (
+            srgb_to_linear(self.r()),
+            srgb_to_linear(self.g()),
+            srgb_to_linear(self.b()),
+            self.a(),
+        )
+    }
+
+    /// Creates a [Color] from CIE XYZ values, relative to the D65 white point, and an alpha.
+    ///
+    /// `y` is the relative luminance, where `1.0` is the luminance of white.
+    #[allow(clippy::excessive_precision)]
+    pub fn from_xyz(x: f32, y: f32, z: f32, alpha: f32) -> Self {
+        Self::from_linear_srgb(
+            3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+            -0.9692660 * x + 1.8760108 * y + 0.0415560 * z,
+            0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+            alpha,
+        )
+    }
+
+    /// Get the CIE XYZ values of the color, relative to the D65 white point, as `(x, y, z, alpha)`.
+    ///
+    /// `y` is the relative luminance, where `1.0` is the luminance of white.
+    #[allow(clippy::excessive_precision)]
+    pub fn to_xyz(&self) -> (f32, f32, f32, f32) {
+        let (r, g, b, alpha) = self.to_linear_srgb();
 
-        let hp = hue / (1.0 / 6.0);
-        let c = saturation * value;
-        let x = c * (1.0 - (hp % 2.0 - 1.0).abs());
-        let m = value - c;
-        let mut r = 0.0;
-        let mut g = 0.0;
-        let mut b = 0.0;
-        if hp <= 1.0 {
-            r = c;
-            g = x;
-        } else if hp <= 2.0 {
-            r = x;
-            g = c;
-        } else if hp <= 3.0 {
-            g = c;
-            b = x;
-        } else if hp <= 4.0 {
-            g = x;
-            b = c;
-        } else if hp <= 5.0 {
-            r = x;
-            b = c;
+        (
+            0.4124564 * r + 0.3575761 * g + 0.1804375 * b,
+            0.2126729 * r + 0.7151522 * g + 0.0721750 * b,
+            0.0193339 * r + 0.1191920 * g + 0.9503041 * b,
+            alpha,
+        )
+    }
+
+    /// Creates a [Color] from CIELAB values, relative to the D65 white point, and an alpha.
+    ///
+    /// `l` ranges from `0.0` to `100.0`.
+    pub fn from_lab(l: f32, a: f32, b: f32, alpha: f32) -> Self {
+        let fy = (l + 16.0) / 116.0;
+        let fx = fy + a / 500.0;
+        let fz = fy - b / 200.0;
+
+        let inverse = |f: f32| {
+            if f * f * f > LAB_EPSILON {
+                f * f * f
+            } else {
+                (116.0 * f - 16.0) / LAB_KAPPA
+            }
+        };
+
+        let y = if l > LAB_KAPPA * LAB_EPSILON {
+            fy * fy * fy
         } else {
-            r = c;
-            b = x;
+            l / LAB_KAPPA
+        };
+
+        Self::from_xyz(
+            inverse(fx) * D65_WHITE.x,
+            y * D65_WHITE.y,
+            inverse(fz) * D65_WHITE.z,
+            alpha,
+        )
+    }
+
+    /// Get the CIELAB values of the color, relative to the D65 white point, as `(l, a, b, alpha)`.
+    ///
+    /// `l` ranges from `0.0` to `100.0`.
+    pub fn to_lab(&self) -> (f32, f32, f32, f32) {
+        let (x, y, z, alpha) = self.to_xyz();
+
+        let f = |t: f32| {
+            if t > LAB_EPSILON {
+                t.cbrt()
+            } else {
+                (LAB_KAPPA * t + 16.0) / 116.0
+            }
+        };
+
+        let fx = f(x / D65_WHITE.x);
+        let fy = f(y / D65_WHITE.y);
+        let fz = f(z / D65_WHITE.z);
+
+        (
+            116.0 * fy - 16.0,
+            500.0 * (fx - fy),
+            200.0 * (fy - fz),
+            alpha,
+        )
+    }
+
+    /// Creates a [Color] from CIE LCh values, the cylindrical form of [CIELAB](Self::from_lab), and an alpha.
+    ///
+    /// `hue` is measured in turns, so it wraps around outside of `0.0..1.0`.
+    pub fn from_lch(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Self {
+        let (a, b) = from_polar(chroma, hue);
+        Self::from_lab(lightness, a, b, alpha)
+    }
+
+    /// Get the CIE LCh values of the color, as `(lightness, chroma, hue, alpha)`.
+    ///
+    /// `hue` is measured in turns, from `0.0..1.0`.
+    pub fn to_lch(&self) -> (f32, f32, f32, f32) {
+        let (l, a, b, alpha) = self.to_lab();
+        let (chroma, hue) = to_polar(a, b);
+        (l, chroma, hue, alpha)
+    }
+
+    /// Creates a [Color] from Oklab values and an alpha.
+    ///
+    /// `l` ranges from `0.0` to `1.0`.
+    ///
+    /// Implementation from [Björn Ottosson](https://bottosson.github.io/posts/oklab/).
+    #[allow(clippy::excessive_precision)]
+    pub fn from_oklab(l: f32, a: f32, b: f32, alpha: f32) -> Self {
+        let long = l + 0.3963377774 * a + 0.2158037573 * b;
+        let medium = l - 0.1055613458 * a - 0.0638541728 * b;
+        let short = l - 0.0894841775 * a - 1.2914855480 * b;
+
+        let long = long * long * long;
+        let medium = medium * medium * medium;
+        let short = short * short * short;
+
+        Self::from_linear_srgb(
+            4.0767416621 * long - 3.3077115913 * medium + 0.2309699292 * short,
+            -1.2684380046 * long + 2.6097574011 * medium - 0.3413193965 * short,
+            -0.0041960863 * long - 0.7034186147 * medium + 1.7076147010 * short,
+            alpha,
+        )
+    }
+
+    /// Get the Oklab values of the color, as `(l, a, b, alpha)`.
+    ///
+    /// `l` ranges from `0.0` to `1.0`.
+    ///
+    /// Implementation from [Björn Ottosson](https://bottosson.github.io/posts/oklab/).
+    #[allow(clippy::excessive_precision)]
+    pub fn to_oklab(&self) -> (f32, f32, f32, f32) {
+        let (r, g, b, alpha) = self.to_linear_srgb();
+
+        let long = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let medium = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let short = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+        (
+            0.2104542553 * long + 0.7936177850 * medium - 0.0040720468 * short,
+            1.9779984951 * long - 2.4285922050 * medium + 0.4505937099 * short,
+            0.0259040371 * long + 0.7827717662 * medium - 0.8086757660 * short,
+            alpha,
+        )
+    }
+
+    /// Creates a [Color] from OkLCH values, the cylindrical form of [Oklab](Self::from_oklab), and an alpha.
+    ///
+    /// `hue` is measured in turns, so it wraps around outside of `0.0..1.0`.
+    pub fn from_oklch(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Self {
+        let (a, b) = from_polar(chroma, hue);
+        Self::from_oklab(lightness, a, b, alpha)
+    }
+
+    /// Get the OkLCH values of the color, as `(lightness, chroma, hue, alpha)`.
+    ///
+    /// `hue` is measured in turns, from `0.0..1.0`.
+    pub fn to_oklch(&self) -> (f32, f32, f32, f32) {
+        let (l, a, b, alpha) = self.to_oklab();
+        let (chroma, hue) = to_polar(a, b);
+        (l, chroma, hue, alpha)
+    }
+
+    /// Get the perceptual difference between two colors, using the CIEDE2000 formula.
+    ///
+    /// A difference of about `1.0` is just noticeable. Alpha is ignored.
+    pub fn delta_e(&self, other: &Color) -> f32 {
+        let (l0, a0, b0, _) = self.to_lab();
+        let (l1, a1, b1, _) = other.to_lab();
+        delta_e_2000((l0, a0, b0), (l1, a1, b1))
+    }
+
+    /// Get the perceptual difference between two colors, as their euclidean distance in Oklab.
+    ///
+    /// This is cheaper than [delta_e](Self::delta_e), and a difference of about `0.02` is just noticeable. Alpha is ignored.
+    pub fn delta_e_ok(&self, other: &Color) -> f32 {
+        let (l0, a0, b0, _) = self.to_oklab();
+        let (l1, a1, b1, _) = other.to_oklab();
+        Vec3::new(l0, a0, b0).distance(Vec3::new(l1, a1, b1))
+    }
+
+    /// Mix two colors in linear light.
+    ///
+    /// Unlike the `+`, `-` and `*` operators, which work on the raw sRGB values, this mixes physical amounts of light,
+    /// so a blend between two bright colors doesn't dip darker in the middle.
+    /// A `t` of `0.0` returns `self` and a `t` of `1.0` returns `other`.
+    pub fn mix_linear(&self, other: &Color, t: f32) -> Self {
+        let (r0, g0, b0, a0) = self.to_linear_srgb();
+        let (r1, g1, b1, a1) = other.to_linear_srgb();
+        let mixed = Vec4::new(r0, g0, b0, a0).lerp(Vec4::new(r1, g1, b1, a1), t);
+
+        Self::from_linear_srgb(mixed.x, mixed.y, mixed.z, mixed.w)
+    }
+
+    /// Mix two colors in the Oklab color space.
+    ///
+    /// This keeps the perceived lightness even across the blend.
+    /// A `t` of `0.0` returns `self` and a `t` of `1.0` returns `other`.
+    pub fn mix_oklab(&self, other: &Color, t: f32) -> Self {
+        let (l0, a0, b0, alpha0) = self.to_oklab();
+        let (l1, a1, b1, alpha1) = other.to_oklab();
+        let mixed = Vec4::new(l0, a0, b0, alpha0).lerp(Vec4::new(l1, a1, b1, alpha1), t);
+
+        Self::from_oklab(mixed.x, mixed.y, mixed.z, mixed.w)
+    }
+
+    /// Create a color from a hue in turns, a chroma, an amount to add to each channel, and an alpha.
+    fn from_hue_chroma(hue: f32, chroma: f32, m: f32, alpha: f32) -> Self {
+        let hp = hue.rem_euclid(1.0) * 6.0;
+        let x = chroma * (1.0 - (hp % 2.0 - 1.0).abs());
+
+        let (r, g, b) = match hp as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        Self::new(r + m, g + m, b + m, alpha)
+    }
+
+    /// Get the hue of the color in turns, given its largest channel and chroma.
+    fn hue(&self, max: f32, chroma: f32) -> f32 {
+        if chroma <= 0.0 {
+            return 0.0;
         }
-        r += m;
-        g += m;
-        b += m;
-        Self::new(r, g, b, 1.0)
+
+        let sector = if max == self.r() {
+            (self.g() - self.b()) / chroma
+        } else if max == self.g() {
+            (self.b() - self.r()) / chroma + 2.0
+        } else {
+            (self.r() - self.g()) / chroma + 4.0
+        };
+
+        (sector / 6.0).rem_euclid(1.0)
     }
 
     /// Get as a hex string.
@@ -209,34 +489,315 @@ impl Color {
     /// Parses a hex string.
     ///
     /// The hex *can* include `#` or `0x` at the beginning, but it is not required.
+    /// Accepts the `rgb`, `rgba`, `rrggbb` and `rrggbbaa` forms, where each shorthand digit is repeated.
     /// If the alpha channel is not included, it will default to 1.0
-    pub fn from_hex(hex: &str) -> Result<Self, ParseIntError> {
-        let mut start_index = if hex.starts_with('#') {
-            1
-        } else if hex.starts_with("0x") {
-            2
-        } else {
-            0
+    pub fn from_hex(hex: &str) -> Result<Self, ColorParseError> {
+        let trimmed = hex.trim();
+        let digits = trimmed
+            .strip_prefix('#')
+            .or_else(|| trimmed.strip_prefix("0x"))
+            .unwrap_or(trimmed);
+
+        let values = digits
+            .chars()
+            .map(|c| {
+                c.to_digit(16)
+                    .ok_or(ColorParseError::InvalidHexDigit(c))
+                    .map(|digit| digit as u8)
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        let channels: Vec<u8> = match values.len() {
+            3 | 4 => values.iter().map(|digit| digit << 4 | digit).collect(),
+            6 | 8 => values
+                .chunks(2)
+                .map(|pair| pair[0] << 4 | pair[1])
+                .collect(),
+            0 => return Err(ColorParseError::Empty),
+            length => return Err(ColorParseError::InvalidHexLength(length)),
         };
 
-        let r = u8::from_str_radix(&hex[start_index..start_index + 2], 16)? as f32 / 255.0;
-        start_index += 2;
-        let g = u8::from_str_radix(&hex[start_index..start_index + 2], 16)? as f32 / 255.0;
-        start_index += 2;
-        let b = u8::from_str_radix(&hex[start_index..start_index + 2], 16)? as f32 / 255.0;
+        let channel = |index: usize| channels.get(index).map_or(1.0, |c| *c as f32 / 255.0);
+
+        Ok(Self::new(channel(0), channel(1), channel(2), channel(3)))
+    }
+
+    /// Look up a color by its [CSS name](crate::palette::NAMED_COLORS), ignoring case.
+    ///
+    /// `transparent` is also accepted.
+    pub fn from_name(name: &str) -> Result<Self, ColorParseError> {
+        let name = name.trim().to_ascii_lowercase();
+
+        if name == "transparent" {
+            return Ok(Self::transparent());
+        }
 
-        start_index += 2;
+        named_color(&name).ok_or(ColorParseError::UnknownColor(name))
+    }
 
-        if start_index >= hex.len() {
-            return Ok(Self::new(r, g, b, 1.0));
+    /// Parses a color the way CSS would.
+    ///
+    /// Accepts [hex colors](Self::from_hex) starting with `#`, named colors, `rgb()`, `rgba()`, `hsl()` and `hsla()`,
+    /// with arguments separated by either commas or spaces and an optional `/ alpha`.
+    pub fn from_css(css: &str) -> Result<Self, ColorParseError> {
+        let css = css.trim().to_ascii_lowercase();
+
+        if css.is_empty() {
+            return Err(ColorParseError::Empty);
         }
 
-        let a = u8::from_str_radix(&hex[start_index..start_index + 2], 16)? as f32 / 255.0;
+        if css.starts_with('#') {
+            return Self::from_hex(&css);
+        }
 
-        Ok(Self::new(r, g, b, a))
+        let (function, arguments) = match css.strip_suffix(')').and_then(|css| css.split_once('('))
+        {
+            Some(call) => call,
+            None => return Self::from_name(&css),
+        };
+
+        let arguments: Vec<&str> = arguments
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|argument| !argument.is_empty())
+            .collect();
+
+        if arguments.len() != 3 && arguments.len() != 4 {
+            return Err(ColorParseError::ArgumentCount {
+                function: function.trim().to_string(),
+                found: arguments.len(),
+            });
+        }
+
+        let alpha = match arguments.get(3) {
+            Some(alpha) => parse_css_number(alpha, 1.0)?,
+            None => 1.0,
+        };
+
+        let color = match function.trim() {
+            "rgb" | "rgba" => Self::new(
+                parse_css_number(arguments[0], 255.0)?,
+                parse_css_number(arguments[1], 255.0)?,
+                parse_css_number(arguments[2], 255.0)?,
+                1.0,
+            ),
+            "hsl" | "hsla" => Self::from_hsl(
+                parse_css_hue(arguments[0])?,
+                parse_css_number(arguments[1], 100.0)?,
+                parse_css_number(arguments[2], 100.0)?,
+                1.0,
+            ),
+            _ => return Err(ColorParseError::UnknownColor(css)),
+        };
+
+        Ok(Self::new(
+            color.r().clamp(0.0, 1.0),
+            color.g().clamp(0.0, 1.0),
+            color.b().clamp(0.0, 1.0),
+            alpha.clamp(0.0, 1.0),
+        ))
+    }
+}
+
+/// An error that occurs when parsing a [Color] from text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColorParseError {
+    /// The input was empty.
+    Empty,
+    /// The input isn't a known color name or color function.
+    UnknownColor(String),
+    /// A color function was given the wrong number of arguments.
+    ArgumentCount {
+        /// The name of the function, like `rgb`.
+        function: String,
+        /// How many arguments were found.
+        found: usize,
+    },
+    /// An argument to a color function isn't a valid number.
+    InvalidNumber(String),
+    /// A hex color has a number of digits other than 3, 4, 6 or 8.
+    InvalidHexLength(usize),
+    /// A hex color contains a character that isn't a hex digit.
+    InvalidHexDigit(char),
+}
+
+impl Display for ColorParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorParseError::Empty => write!(f, "cannot parse a color from an empty string"),
+            ColorParseError::UnknownColor(color) => write!(f, "unknown color \"{}\"", color),
+            ColorParseError::ArgumentCount { function, found } => write!(
+                f,
+                "{}() takes 3 or 4 arguments, but {} were given",
+                function, found
+            ),
+            ColorParseError::InvalidNumber(number) => {
+                write!(f, "\"{}\" is not a valid number", number)
+            }
+            ColorParseError::InvalidHexLength(length) => write!(
+                f,
+                "hex colors have 3, 4, 6 or 8 digits, but {} were given",
+                length
+            ),
+            ColorParseError::InvalidHexDigit(c) => {
+                write!(f, "{:?} is not a hex digit", c)
+            }
+        }
+    }
+}
+
+impl Error for ColorParseError {}
+
+/// Parses anything accepted by [Color::from_css].
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_css(s)
     }
 }
 
+/// Parse a CSS number or percentage, mapping `100%` and `max` to `1.0`.
+fn parse_css_number(argument: &str, max: f32) -> Result<f32, ColorParseError> {
+    let invalid = || ColorParseError::InvalidNumber(argument.to_string());
+
+    let value = match argument.strip_suffix('%') {
+        Some(percentage) => percentage.parse::<f32>().map_err(|_| invalid())? / 100.0,
+        None => argument.parse::<f32>().map_err(|_| invalid())? / max,
+    };
+
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Parse a CSS hue into turns. Bare numbers are in degrees.
+fn parse_css_hue(argument: &str) -> Result<f32, ColorParseError> {
+    let units = [("deg", 360.0), ("grad", 400.0), ("rad", TAU), ("turn", 1.0)];
+
+    let (number, per_turn) = units
+        .iter()
+        .find_map(|(unit, per_turn)| Some((argument.strip_suffix(unit)?, *per_turn)))
+        .unwrap_or((argument, 360.0));
+
+    match number.parse::<f32>() {
+        Ok(hue) if hue.is_finite() => Ok(hue / per_turn),
+        _ => Err(ColorParseError::InvalidNumber(argument.to_string())),
+    }
+}
+
+/// Convert a single sRGB-encoded channel to linear light.
+pub(crate) fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a single linear light channel to sRGB encoding.
+pub(crate) fn linear_to_srgb(channel: f32) -> f32 {
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// The D65 white point in CIE XYZ.
+const D65_WHITE: Vec3 = glam::const_vec3!([0.95047, 1.0, 1.08883]);
+/// The CIE standard's `ε`, where CIELAB switches from a cube root to a linear segment.
+const LAB_EPSILON: f32 = 216.0 / 24389.0;
+/// The CIE standard's `κ`.
+const LAB_KAPPA: f32 = 24389.0 / 27.0;
+
+/// Convert polar coordinates, with the angle in turns, to cartesian coordinates.
+fn from_polar(radius: f32, turns: f32) -> (f32, f32) {
+    let (sin, cos) = (turns * TAU).sin_cos();
+    (radius * cos, radius * sin)
+}
+
+/// Convert cartesian coordinates to polar coordinates, with the angle in turns.
+fn to_polar(x: f32, y: f32) -> (f32, f32) {
+    ((x * x + y * y).sqrt(), (y.atan2(x) / TAU).rem_euclid(1.0))
+}
+
+/// The CIEDE2000 color difference between two CIELAB colors.
+///
+/// Implementation from [Sharma et al.](https://hajim.rochester.edu/ece/sites/gsharma/ciede2000/ciede2000noteCRNA.pdf).
+/// The math is done in [f64], since the formula is sensitive to rounding around hue discontinuities.
+fn delta_e_2000(lab0: (f32, f32, f32), lab1: (f32, f32, f32)) -> f32 {
+    let (l0, a0, b0) = (lab0.0 as f64, lab0.1 as f64, lab0.2 as f64);
+    let (l1, a1, b1) = (lab1.0 as f64, lab1.1 as f64, lab1.2 as f64);
+
+    let pow7 = |x: f64| x.powi(7);
+    let twenty_five_7 = pow7(25.0);
+
+    let c_bar = ((a0 * a0 + b0 * b0).sqrt() + (a1 * a1 + b1 * b1).sqrt()) / 2.0;
+    let g = 0.5 * (1.0 - (pow7(c_bar) / (pow7(c_bar) + twenty_five_7)).sqrt());
+
+    let a0 = (1.0 + g) * a0;
+    let a1 = (1.0 + g) * a1;
+
+    let c0 = (a0 * a0 + b0 * b0).sqrt();
+    let c1 = (a1 * a1 + b1 * b1).sqrt();
+
+    let hue = |a: f64, b: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let h0 = hue(a0, b0);
+    let h1 = hue(a1, b1);
+
+    let delta_l = l1 - l0;
+    let delta_c = c1 - c0;
+
+    let delta_h = if c0 * c1 == 0.0 {
+        0.0
+    } else if (h1 - h0).abs() <= 180.0 {
+        h1 - h0
+    } else if h1 - h0 > 180.0 {
+        h1 - h0 - 360.0
+    } else {
+        h1 - h0 + 360.0
+    };
+    let delta_h = 2.0 * (c0 * c1).sqrt() * (delta_h.to_radians() / 2.0).sin();
+
+    let l_bar = (l0 + l1) / 2.0;
+    let c_bar = (c0 + c1) / 2.0;
+    let h_bar = if c0 * c1 == 0.0 {
+        h0 + h1
+    } else if (h0 - h1).abs() <= 180.0 {
+        (h0 + h1) / 2.0
+    } else if h0 + h1 < 360.0 {
+        (h0 + h1 + 360.0) / 2.0
+    } else {
+        (h0 + h1 - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+
+    let delta_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (pow7(c_bar) / (pow7(c_bar) + twenty_five_7)).sqrt();
+    let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar;
+    let s_h = 1.0 + 0.015 * c_bar * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let l = delta_l / s_l;
+    let c = delta_c / s_c;
+    let h = delta_h / s_h;
+
+    (l * l + c * c + h * h + r_t * c * h).sqrt() as f32
+}
+
 #[cfg(feature = "tiny_skia_renderer")]
 impl From<Rgb<u8>> for Color {
     fn from(rgb: Rgb<u8>) -> Self {
@@ -420,3 +981,267 @@ impl Rem<Color> for Color {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 0.001;
+
+    /// Assert that two triples are within `epsilon` of each other.
+    #[inline]
+    fn assert_values_eq(a: (f32, f32, f32, f32), b: (f32, f32, f32, f32), epsilon: f32) {
+        if !Vec4::from(a).abs_diff_eq(Vec4::from(b), epsilon) {
+            panic!("assertion failed: {:?}, {:?}", a, b);
+        }
+    }
+
+    /// A spread of colors covering the corners and interior of the RGB cube.
+    fn sample_colors() -> Vec<Color> {
+        let steps = [0.0, 0.2, 0.5, 0.8, 1.0];
+        let mut colors = Vec::new();
+
+        for r in steps {
+            for g in steps {
+                for b in steps {
+                    colors.push(Color::new(r, g, b, 1.0));
+                }
+            }
+        }
+
+        colors
+    }
+
+    /// Verify conversions against published reference values.
+    #[test]
+    fn reference_values() {
+        let red = Color::red();
+        let slate = Color::new(0.2, 0.4, 0.6, 1.0);
+
+        assert_values_eq(
+            slate.to_hsv(),
+            (210.0 / 360.0, 2.0 / 3.0, 0.6, 1.0),
+            EPSILON,
+        );
+        assert_values_eq(slate.to_hsl(), (210.0 / 360.0, 0.5, 0.4, 1.0), EPSILON);
+        assert_values_eq(
+            slate.to_linear_srgb(),
+            (0.0331, 0.1329, 0.3185, 1.0),
+            EPSILON,
+        );
+
+        // Bruce Lindbloom's calculator, sRGB with a D65 reference white.
+        assert_values_eq(red.to_xyz(), (0.4125, 0.2127, 0.0193, 1.0), EPSILON);
+        assert_values_eq(red.to_lab(), (53.2408, 80.0925, 67.2032, 1.0), 0.01);
+        assert_values_eq(
+            red.to_lch(),
+            (53.2408, 104.5518, 39.9990 / 360.0, 1.0),
+            0.01,
+        );
+        assert_values_eq(Color::white().to_lab(), (100.0, 0.0, 0.0, 1.0), 0.01);
+
+        // Björn Ottosson's reference implementation.
+        assert_values_eq(red.to_oklab(), (0.62796, 0.22486, 0.12585, 1.0), EPSILON);
+        assert_values_eq(
+            red.to_oklch(),
+            (0.62796, 0.25768, 29.2339 / 360.0, 1.0),
+            EPSILON,
+        );
+        assert_values_eq(Color::white().to_oklab(), (1.0, 0.0, 0.0, 1.0), EPSILON);
+    }
+
+    /// Verify that every conversion round-trips back to the original color, including its alpha.
+    #[test]
+    fn round_trips() {
+        for color in sample_colors()
+            .into_iter()
+            .flat_map(|color| [color, color.with_a(0.25)])
+        {
+            let (h, s, v, alpha) = color.to_hsv();
+            let (hh, hs, hl, _) = color.to_hsl();
+            let (lr, lg, lb, _) = color.to_linear_srgb();
+            let (x, y, z, _) = color.to_xyz();
+            let (l, a, b, _) = color.to_lab();
+            let (cl, cc, ch, _) = color.to_lch();
+            let (ol, oa, ob, _) = color.to_oklab();
+            let (kl, kc, kh, _) = color.to_oklch();
+            assert_eq!(alpha, color.a());
+
+            for converted in [
+                Color::from_hsv(h, s, v, alpha),
+                Color::from_hsl(hh, hs, hl, alpha),
+                Color::from_linear_srgb(lr, lg, lb, alpha),
+                Color::from_xyz(x, y, z, alpha),
+                Color::from_lab(l, a, b, alpha),
+                Color::from_lch(cl, cc, ch, alpha),
+                Color::from_oklab(ol, oa, ob, alpha),
+                Color::from_oklch(kl, kc, kh, alpha),
+            ] {
+                assert!(
+                    color.inner.abs_diff_eq(converted.inner, EPSILON),
+                    "{:?} became {:?}",
+                    color,
+                    converted
+                );
+            }
+        }
+    }
+
+    /// Verify that HSV accepts out-of-range input instead of panicking.
+    #[test]
+    fn hsv_wraps_and_clamps() {
+        assert!(Color::from_hsv(1.5, 2.0, 1.0, 1.0)
+            .inner
+            .abs_diff_eq(Color::new(0.0, 1.0, 1.0, 1.0).inner, EPSILON));
+        assert!(Color::from_hsv(-0.25, 1.0, -1.0, 1.0)
+            .inner
+            .abs_diff_eq(Color::black().inner, EPSILON));
+    }
+
+    /// Verify CIEDE2000 against the test data published by Sharma et al.
+    #[test]
+    fn ciede2000_reference_pairs() {
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, 0.0, 0.0), (50.0, -1.0, 2.0), 2.3669),
+            ((50.0, 2.49, -0.001), (50.0, -2.49, 0.0011), 7.2195),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            (
+                (60.2574, -34.0099, 36.2677),
+                (60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+            ((2.0776, 0.0795, -1.135), (0.9033, -0.0636, -0.5514), 0.9082),
+        ];
+
+        for (a, b, expected) in pairs {
+            let delta = delta_e_2000(a, b);
+            assert!(
+                (delta - expected).abs() < 0.0001,
+                "{} != {}",
+                delta,
+                expected
+            );
+        }
+
+        assert_eq!(Color::red().delta_e(&Color::red()), 0.0);
+        assert!(Color::red().delta_e_ok(&Color::blue()) > 0.5);
+    }
+
+    /// Verify that linear mixing keeps the middle of a red to green blend brighter than the sRGB average.
+    #[test]
+    fn linear_mix_midpoint() {
+        let srgb = Color::red() * 0.5 + Color::green() * 0.5;
+        let linear = Color::red().mix_linear(&Color::green(), 0.5);
+
+        assert!((srgb.r() - 0.5).abs() < 0.001);
+        assert!((linear.r() - 0.7354).abs() < 0.001);
+        assert!((linear.g() - 0.7354).abs() < 0.001);
+        assert_eq!(linear.b(), 0.0);
+        assert!(
+            Color::red()
+                .mix_linear(&Color::green(), 1.0)
+                .delta_e_ok(&Color::green())
+                < 0.0001
+        );
+    }
+
+    /// Verify that CSS color syntax is parsed, and malformed input is rejected with an error.
+    #[test]
+    fn css_colors() {
+        assert_eq!(Color::from_css("Red"), Ok(Color::red()));
+        assert_eq!(Color::from_css(" transparent "), Ok(Color::transparent()));
+        assert_eq!(
+            Color::from_css("rgb(255, 0, 0)"),
+            Color::from_css("rgb(100% 0% 0%)")
+        );
+        assert_eq!(
+            Color::from_css("rgba(0, 0, 255, 0.5)"),
+            Ok(Color::blue().with_a(0.5))
+        );
+        assert_eq!(
+            Color::from_css("rgb(0 0 255 / 50%)"),
+            Ok(Color::blue().with_a(0.5))
+        );
+
+        let green = Color::from_css("hsl(120, 100%, 50%)").unwrap();
+        assert!(green.delta_e_ok(&Color::green()) < 0.0001);
+        assert_eq!(
+            Color::from_css("hsl(0.3333turn 100% 50%)").unwrap(),
+            Color::from_hsl(0.3333, 1.0, 0.5, 1.0)
+        );
+
+        assert_eq!(Color::from_css(""), Err(ColorParseError::Empty));
+        assert_eq!(
+            Color::from_css("blurple"),
+            Err(ColorParseError::UnknownColor("blurple".to_string()))
+        );
+        assert_eq!(
+            Color::from_css("rgb(1, 2)"),
+            Err(ColorParseError::ArgumentCount {
+                function: "rgb".to_string(),
+                found: 2
+            })
+        );
+        assert_eq!(
+            Color::from_css("rgb(1, 2, x)"),
+            Err(ColorParseError::InvalidNumber("x".to_string()))
+        );
+    }
+
+    /// Verify that every hex form is parsed, and that bad input is an error instead of a panic.
+    #[test]
+    fn hex_colors() {
+        let expected = Color::new(1.0, 0.8, 0.0, 1.0);
+        assert_eq!(Color::from_hex("#fc0"), Ok(expected));
+        assert_eq!(Color::from_hex("ffcc00"), Ok(expected));
+        assert_eq!(Color::from_hex("0xFFCC00ff"), Ok(expected));
+        assert_eq!(Color::from_hex("#fc00"), Ok(expected.with_a(0.0)));
+        assert_eq!("#ffcc00".parse(), Ok(expected));
+
+        assert_eq!(Color::from_hex("#"), Err(ColorParseError::Empty));
+        assert_eq!(
+            Color::from_hex("#ff"),
+            Err(ColorParseError::InvalidHexLength(2))
+        );
+        assert_eq!(
+            Color::from_hex("#ffé"),
+            Err(ColorParseError::InvalidHexDigit('é'))
+        );
+    }
+
+    /// Verify that parsing never panics, using many pseudo-random strings.
+    #[test]
+    fn parsing_never_panics() {
+        const ALPHABET: [char; 24] = [
+            '#', '0', '9', 'a', 'f', 'F', 'g', 'x', '(', ')', ',', '/', ' ', '%', '.', '-', 'e',
+            'r', 'b', 'h', 'l', 'é', '😀', '\u{0}',
+        ];
+
+        // A xorshift generator, so the test is reproducible without extra dependencies.
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..20_000 {
+            let length = next() % 12;
+            let input: String = (0..length)
+                .map(|_| ALPHABET[(next() % ALPHABET.len() as u64) as usize])
+                .collect();
+
+            let _ = Color::from_hex(&input);
+            let _ = input.parse::<Color>();
+            let _ = Color::from_css(&format!("rgb({})", input));
+            let _ = Color::from_css(&format!("hsl({})", input));
+        }
+
+        for _ in 0..1000 {
+            let color = Color::from_hex(&format!("#{:08x}", next() as u32)).unwrap();
+            assert_eq!(Color::from_hex(&color.as_hex(true)), Ok(color));
+        }
+    }
+}
@@ -0,0 +1,501 @@
+use glam::{Vec2, Vec3};
+
+/// A cubic bezier curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CubicBezier {
+    /// Where the curve starts.
+    pub start: Vec2,
+    /// The control point that pulls on the start of the curve.
+    pub control_0: Vec2,
+    /// The control point that pulls on the end of the curve.
+    pub control_1: Vec2,
+    /// Where the curve ends.
+    pub end: Vec2,
+}
+
+impl CubicBezier {
+    /// Create a new [CubicBezier].
+    pub fn new<P: Into<Vec2>>(start: P, control_0: P, control_1: P, end: P) -> Self {
+        Self {
+            start: start.into(),
+            control_0: control_0.into(),
+            control_1: control_1.into(),
+            end: end.into(),
+        }
+    }
+
+    /// Get the point on the curve at `t`, which ranges from `0` at the start to `1` at the end.
+    pub fn point(&self, t: f32) -> Vec2 {
+        let u = 1.0 - t;
+        self.start * (u * u * u)
+            + self.control_0 * (3.0 * u * u * t)
+            + self.control_1 * (3.0 * u * t * t)
+            + self.end * (t * t * t)
+    }
+
+    /// Get the derivative of the curve at `t`.
+    fn derivative(&self, t: f32) -> Vec2 {
+        let u = 1.0 - t;
+        (self.control_0 - self.start) * (3.0 * u * u)
+            + (self.control_1 - self.control_0) * (6.0 * u * t)
+            + (self.end - self.control_1) * (3.0 * t * t)
+    }
+
+    /// Get the second derivative of the curve at `t`.
+    fn second_derivative(&self, t: f32) -> Vec2 {
+        (self.control_1 - self.control_0 * 2.0 + self.start) * (6.0 * (1.0 - t))
+            + (self.end - self.control_1 * 2.0 + self.control_0) * (6.0 * t)
+    }
+
+    /// Fit as few cubic bezier curves as possible through a dense polyline,
+    /// so that no point is further than `tolerance` from the curves.
+    ///
+    /// Uses least squares fitting, splitting the polyline where the error is largest, as described by Philip J. Schneider
+    /// in "An Algorithm for Automatically Fitting Digitized Curves".
+    pub fn fit(points: &[Vec2], tolerance: f32) -> Vec<Self> {
+        let mut points = points.to_vec();
+        points.dedup();
+
+        let mut curves = Vec::new();
+        if points.len() < 2 {
+            return curves;
+        }
+
+        let start_tangent = (points[1] - points[0]).normalize();
+        let end_tangent = (points[points.len() - 2] - points[points.len() - 1]).normalize();
+        fit_range(
+            &points,
+            start_tangent,
+            end_tangent,
+            tolerance * tolerance,
+            &mut curves,
+        );
+
+        curves
+    }
+}
+
+/// Fit curves to a range of points, given the direction the curves leave each end in.
+fn fit_range(
+    points: &[Vec2],
+    start_tangent: Vec2,
+    end_tangent: Vec2,
+    max_error: f32,
+    curves: &mut Vec<CubicBezier>,
+) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+
+    if points.len() == 2 {
+        let handle = first.distance(last) / 3.0;
+        curves.push(CubicBezier::new(
+            first,
+            first + start_tangent * handle,
+            last + end_tangent * handle,
+            last,
+        ));
+        return;
+    }
+
+    // Parameterize by the distance along the polyline.
+    let mut parameters = Vec::with_capacity(points.len());
+    let mut length = 0.0;
+    for (index, point) in points.iter().enumerate() {
+        if index > 0 {
+            length += point.distance(points[index - 1]);
+        }
+        parameters.push(length);
+    }
+    for parameter in &mut parameters {
+        *parameter /= length;
+    }
+
+    let mut curve = least_squares(points, &parameters, start_tangent, end_tangent);
+    let (mut error, mut split) = largest_error(points, &parameters, &curve);
+
+    // Close fits are usually improved by moving each point's parameter closer to the nearest point on the curve.
+    if error < max_error * 4.0 {
+        for _ in 0..4 {
+            if error < max_error {
+                break;
+            }
+
+            for (parameter, point) in parameters.iter_mut().zip(points) {
+                *parameter = newton_raphson(&curve, *point, *parameter);
+            }
+            curve = least_squares(points, &parameters, start_tangent, end_tangent);
+            (error, split) = largest_error(points, &parameters, &curve);
+        }
+    }
+
+    if error < max_error {
+        curves.push(curve);
+        return;
+    }
+
+    let mut center_tangent = (points[split - 1] - points[split + 1]).normalize_or_zero();
+    if center_tangent == Vec2::ZERO {
+        center_tangent = (points[split] - points[split - 1]).perp().normalize();
+    }
+
+    fit_range(
+        &points[..=split],
+        start_tangent,
+        center_tangent,
+        max_error,
+        curves,
+    );
+    fit_range(
+        &points[split..],
+        -center_tangent,
+        end_tangent,
+        max_error,
+        curves,
+    );
+}
+
+/// Find the curve that best fits the points at their parameters, with its control points along the tangents.
+fn least_squares(
+    points: &[Vec2],
+    parameters: &[f32],
+    start_tangent: Vec2,
+    end_tangent: Vec2,
+) -> CubicBezier {
+    let (first, last) = (points[0], points[points.len() - 1]);
+
+    let mut c = [[0.0; 2]; 2];
+    let mut x = [0.0; 2];
+
+    for (point, t) in points.iter().zip(parameters) {
+        let u = 1.0 - t;
+        let a0 = start_tangent * (3.0 * u * u * t);
+        let a1 = end_tangent * (3.0 * u * t * t);
+
+        c[0][0] += a0.dot(a0);
+        c[0][1] += a0.dot(a1);
+        c[1][1] += a1.dot(a1);
+
+        let shortfall = *point - CubicBezier::new(first, first, last, last).point(*t);
+        x[0] += a0.dot(shortfall);
+        x[1] += a1.dot(shortfall);
+    }
+    c[1][0] = c[0][1];
+
+    let determinant = c[0][0] * c[1][1] - c[1][0] * c[0][1];
+    let (mut alpha_0, mut alpha_1) = if determinant.abs() > f32::EPSILON {
+        (
+            (x[0] * c[1][1] - x[1] * c[0][1]) / determinant,
+            (c[0][0] * x[1] - c[1][0] * x[0]) / determinant,
+        )
+    } else {
+        (0.0, 0.0)
+    };
+
+    // Fall back to a third of the distance when the fit pulls the control points too close, or backwards.
+    let distance = first.distance(last);
+    let epsilon = distance * 1e-6;
+    if alpha_0 < epsilon || alpha_1 < epsilon {
+        alpha_0 = distance / 3.0;
+        alpha_1 = distance / 3.0;
+    }
+
+    CubicBezier::new(
+        first,
+        first + start_tangent * alpha_0,
+        last + end_tangent * alpha_1,
+        last,
+    )
+}
+
+/// Find the largest squared distance between a point and the curve at its parameter, along with that point's index.
+fn largest_error(points: &[Vec2], parameters: &[f32], curve: &CubicBezier) -> (f32, usize) {
+    let mut largest = (0.0, points.len() / 2);
+
+    for index in 1..points.len() - 1 {
+        let error = curve
+            .point(parameters[index])
+            .distance_squared(points[index]);
+        if error >= largest.0 {
+            largest = (error, index);
+        }
+    }
+
+    largest
+}
+
+/// Improve the parameter of the point on `curve` that is closest to `point`.
+fn newton_raphson(curve: &CubicBezier, point: Vec2, t: f32) -> f32 {
+    let offset = curve.point(t) - point;
+    let derivative = curve.derivative(t);
+
+    let numerator = offset.dot(derivative);
+    let denominator = derivative.length_squared() + offset.dot(curve.second_derivative(t));
+
+    if denominator.abs() < f32::EPSILON {
+        t
+    } else {
+        (t - numerator / denominator).clamp(0.0, 1.0)
+    }
+}
+
+/// A Non-Uniform Rational B-Spline.
+///
+/// Each control point pulls the curve towards it in proportion to its weight,
+/// and the knot vector decides which span of the curve each control point affects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Nurbs {
+    degree: usize,
+    control_points: Vec<Vec2>,
+    weights: Vec<f32>,
+    knots: Vec<f32>,
+}
+
+impl Nurbs {
+    /// Create a new [Nurbs].
+    ///
+    /// Returns `None` unless there are more control points than the degree, a weight for each control point,
+    /// and `control_points.len() + degree + 1` knots that never decrease.
+    pub fn new(
+        degree: usize,
+        control_points: Vec<Vec2>,
+        weights: Vec<f32>,
+        knots: Vec<f32>,
+    ) -> Option<Self> {
+        let valid = degree > 0
+            && control_points.len() > degree
+            && weights.len() == control_points.len()
+            && knots.len() == control_points.len() + degree + 1
+            && knots.windows(2).all(|pair| pair[0] <= pair[1])
+            && knots[degree] < knots[control_points.len()];
+
+        valid.then_some(Self {
+            degree,
+            control_points,
+            weights,
+            knots,
+        })
+    }
+
+    /// Create a new [Nurbs] with uniformly spaced knots, clamped so the curve starts and ends at the first and last control points.
+    ///
+    /// The degree is lowered if there aren't enough control points for it.
+    /// Returns `None` if there are fewer than two control points, or a different number of weights.
+    pub fn clamped(degree: usize, control_points: Vec<Vec2>, weights: Vec<f32>) -> Option<Self> {
+        let degree = degree.min(control_points.len().saturating_sub(1));
+        let spans = control_points.len().saturating_sub(degree);
+
+        let knots = (0..control_points.len() + degree + 1)
+            .map(|index| index.saturating_sub(degree).min(spans) as f32 / spans.max(1) as f32)
+            .collect();
+
+        Self::new(degree, control_points, weights, knots)
+    }
+
+    /// Create a new uniform cubic B-spline, clamped to its first and last control points.
+    ///
+    /// This is a [Nurbs] where every control point has the same weight.
+    pub fn b_spline(control_points: Vec<Vec2>) -> Option<Self> {
+        let weights = vec![1.0; control_points.len()];
+        Self::clamped(3, control_points, weights)
+    }
+
+    /// Get the control points.
+    pub fn control_points(&self) -> &[Vec2] {
+        &self.control_points
+    }
+
+    /// Get the range of parameters the curve is defined over.
+    pub fn domain(&self) -> (f32, f32) {
+        (
+            self.knots[self.degree],
+            self.knots[self.control_points.len()],
+        )
+    }
+
+    /// Get the point on the curve at `t`, which is clamped to the [domain](Self::domain).
+    pub fn point(&self, t: f32) -> Vec2 {
+        let (start, end) = self.domain();
+        let t = t.clamp(start, end);
+        let degree = self.degree;
+
+        // Find the knot span that contains `t`, using the last non-empty span at the very end.
+        let span = (degree..self.control_points.len())
+            .rev()
+            .find(|span| self.knots[*span] <= t && self.knots[*span] < self.knots[span + 1])
+            .unwrap_or(degree);
+
+        // De Boor's algorithm, in homogeneous coordinates.
+        let mut points: Vec<Vec3> = (span - degree..=span)
+            .map(|index| {
+                (self.control_points[index] * self.weights[index]).extend(self.weights[index])
+            })
+            .collect();
+
+        for r in 1..=degree {
+            for j in (r..=degree).rev() {
+                let left = self.knots[j + span - degree];
+                let right = self.knots[j + 1 + span - r];
+                let alpha = if right > left {
+                    (t - left) / (right - left)
+                } else {
+                    0.0
+                };
+
+                points[j] = points[j - 1] * (1.0 - alpha) + points[j] * alpha;
+            }
+        }
+
+        let point = points[degree];
+        point.truncate() / point.z
+    }
+}
+
+/// How the points of a Catmull-Rom spline are spaced along it, which changes how tightly it turns at each point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CatmullRom {
+    /// Every point is equally far apart. Can overshoot and loop when points are unevenly spaced.
+    Uniform,
+    /// Points are spaced by the square root of the distance between them. Never forms loops or cusps.
+    Centripetal,
+}
+
+/// Convert a Catmull-Rom spline through `points` into cubic bezier curves.
+///
+/// `tension` ranges from `0`, a Catmull-Rom spline, to `1`, straight lines.
+pub(crate) fn catmull_rom(points: &[Vec2], tension: f32, kind: CatmullRom) -> Vec<CubicBezier> {
+    let mut points = points.to_vec();
+    points.dedup();
+
+    if points.len() < 2 {
+        return Vec::new();
+    }
+
+    // Reflect the second and second to last points, so the curve heads straight for its neighbours at the ends.
+    let count = points.len();
+    let before = points[0] * 2.0 - points[1];
+    let after = points[count - 1] * 2.0 - points[count - 2];
+
+    let alpha = match kind {
+        CatmullRom::Uniform => 0.0,
+        CatmullRom::Centripetal => 0.5,
+    };
+    let interval = |a: Vec2, b: Vec2| a.distance(b).powf(alpha).max(f32::EPSILON);
+    let scale = (1.0 - tension) / 3.0;
+
+    (0..count - 1)
+        .map(|index| {
+            let p0 = if index == 0 {
+                before
+            } else {
+                points[index - 1]
+            };
+            let (p1, p2) = (points[index], points[index + 1]);
+            let p3 = points.get(index + 2).copied().unwrap_or(after);
+
+            let (d0, d1, d2) = (interval(p0, p1), interval(p1, p2), interval(p2, p3));
+
+            // Tangents of the spline at each end of the segment, scaled to the segment's interval.
+            let m1 = ((p1 - p0) / d0 - (p2 - p0) / (d0 + d1) + (p2 - p1) / d1) * d1;
+            let m2 = ((p2 - p1) / d1 - (p3 - p1) / (d1 + d2) + (p3 - p2) / d2) * d1;
+
+            CubicBezier::new(p1, p1 + m1 * scale, p2 - m2 * scale, p2)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 0.0001;
+
+    /// Verify that Catmull-Rom splines pass through every point, and straighten out with full tension.
+    #[test]
+    fn catmull_rom_interpolates() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.1, 0.0),
+            Vec2::new(3.0, 0.5),
+        ];
+
+        for kind in [CatmullRom::Uniform, CatmullRom::Centripetal] {
+            let curves = catmull_rom(&points, 0.0, kind);
+            assert_eq!(curves.len(), 3);
+
+            for (curve, pair) in curves.iter().zip(points.windows(2)) {
+                assert!(curve.point(0.0).abs_diff_eq(pair[0], EPSILON));
+                assert!(curve.point(1.0).abs_diff_eq(pair[1], EPSILON));
+            }
+
+            // Neighbouring curves meet smoothly.
+            for pair in curves.windows(2) {
+                let incoming = (pair[0].end - pair[0].control_1).normalize();
+                let outgoing = (pair[1].control_0 - pair[1].start).normalize();
+                assert!(incoming.abs_diff_eq(outgoing, EPSILON));
+            }
+        }
+
+        let straight = catmull_rom(&points, 1.0, CatmullRom::Uniform);
+        assert!(straight[0]
+            .point(0.5)
+            .abs_diff_eq(Vec2::splat(0.5), EPSILON));
+    }
+
+    /// Verify that clamped B-splines start and end at their control points, and that weights can draw exact circles.
+    #[test]
+    fn nurbs_evaluation() {
+        let spline = Nurbs::b_spline(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(2.0, -1.0),
+            Vec2::new(3.0, 1.0),
+            Vec2::new(4.0, 0.0),
+        ])
+        .unwrap();
+
+        assert_eq!(spline.domain(), (0.0, 1.0));
+        assert!(spline.point(0.0).abs_diff_eq(Vec2::ZERO, EPSILON));
+        assert!(spline.point(1.0).abs_diff_eq(Vec2::new(4.0, 0.0), EPSILON));
+
+        // A quarter circle is a quadratic NURBS with a middle weight of 1/sqrt(2).
+        let quarter = Nurbs::new(
+            2,
+            vec![Vec2::X, Vec2::ONE, Vec2::Y],
+            vec![1.0, std::f32::consts::FRAC_1_SQRT_2, 1.0],
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        )
+        .unwrap();
+
+        for n in 0..=10 {
+            assert!((quarter.point(n as f32 / 10.0).length() - 1.0).abs() < EPSILON);
+        }
+
+        assert!(Nurbs::new(3, vec![Vec2::ZERO; 3], vec![1.0; 3], vec![0.0; 7]).is_none());
+    }
+
+    /// Verify that fitting a dense polyline produces a few curves that stay within the tolerance.
+    #[test]
+    fn fits_dense_polyline() {
+        let points: Vec<Vec2> = (0..=500)
+            .map(|n| {
+                let x = n as f32 / 100.0;
+                Vec2::new(x, x.sin())
+            })
+            .collect();
+
+        let curves = CubicBezier::fit(&points, 0.01);
+        assert!(!curves.is_empty() && curves.len() <= 6);
+        assert_eq!(curves[0].start, points[0]);
+        assert_eq!(curves[curves.len() - 1].end, points[500]);
+
+        for point in &points {
+            let closest = curves
+                .iter()
+                .flat_map(|curve| (0..=200).map(move |n| curve.point(n as f32 / 200.0)))
+                .map(|sample| sample.distance(*point))
+                .fold(f32::INFINITY, f32::min);
+
+            assert!(closest < 0.012);
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::Path,
+};
+
+use glam::UVec2;
+
+use crate::{Canvas, Color, Page, RenderError};
+
+/// Settings shared by every exporter.
+///
+/// Not every format supports every setting. Settings a format can't use are ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportOptions {
+    /// The size of the output, in pixels.
+    pub size: UVec2,
+    /// The color drawn behind everything else, if any.
+    ///
+    /// Formats without an alpha channel, like JPEG, show black where there is no background.
+    pub background: Option<Color>,
+    /// Whether to antialias raster output.
+    pub antialias: bool,
+    /// Whether the camera's height (`true`) or width (`false`) fills the output.
+    pub preserve_height: bool,
+    /// The quality of lossy formats, from `1` to `100`.
+    pub quality: u8,
+    /// The resolution to record in the file's metadata, in dots per inch.
+    ///
+    /// Only written to formats that support it: PNG and JPEG.
+    pub dpi: Option<f32>,
+    /// The physical page being exported, if any.
+    ///
+    /// Vector formats use it to write their size in physical units. Set it with [with_page](Self::with_page).
+    pub page: Option<Page>,
+}
+
+impl ExportOptions {
+    /// Create [ExportOptions] for an output of `size` pixels, leaving everything else at its default.
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            ..Default::default()
+        }
+    }
+
+    /// Set the background color.
+    #[inline]
+    pub fn with_background(mut self, background: Option<Color>) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Set whether raster output is antialiased.
+    #[inline]
+    pub fn with_antialias(mut self, antialias: bool) -> Self {
+        self.antialias = antialias;
+        self
+    }
+
+    /// Set whether the camera's height or width fills the output.
+    #[inline]
+    pub fn with_preserve_height(mut self, preserve_height: bool) -> Self {
+        self.preserve_height = preserve_height;
+        self
+    }
+
+    /// Set the quality of lossy formats, from `1` to `100`.
+    #[inline]
+    pub fn with_quality(mut self, quality: u8) -> Self {
+        self.quality = quality.clamp(1, 100);
+        self
+    }
+
+    /// Set the resolution recorded in the file's metadata.
+    #[inline]
+    pub fn with_dpi(mut self, dpi: Option<f32>) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// Export to a physical [Page], setting the [size](Self::size) and [dpi](Self::dpi) to match it.
+    #[inline]
+    pub fn with_page(mut self, page: Page) -> Self {
+        self.size = page.pixel_size();
+        self.dpi = Some(page.dpi());
+        self.page = Some(page);
+        self
+    }
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            size: UVec2::splat(1000),
+            background: None,
+            antialias: true,
+            preserve_height: true,
+            quality: 90,
+            dpi: None,
+            page: None,
+        }
+    }
+}
+
+/// An error that occurs while exporting a [Canvas].
+#[derive(Debug)]
+pub enum ExportError {
+    /// The path has no file extension to pick a format from.
+    MissingExtension,
+    /// No exporter is registered for the extension.
+    UnsupportedFormat(String),
+    /// Writing the file failed.
+    Io(io::Error),
+    /// Rendering failed.
+    Render(RenderError),
+    /// Encoding the image failed.
+    #[cfg(feature = "tiny_skia_renderer")]
+    Image(image::ImageError),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::MissingExtension => write!(f, "the path has no file extension"),
+            ExportError::UnsupportedFormat(extension) => {
+                write!(f, "no exporter is registered for \"{}\" files", extension)
+            }
+            ExportError::Io(error) => write!(f, "failed to write file: {}", error),
+            ExportError::Render(error) => write!(f, "failed to render: {}", error),
+            #[cfg(feature = "tiny_skia_renderer")]
+            ExportError::Image(error) => write!(f, "failed to encode image: {}", error),
+        }
+    }
+}
+
+impl Error for ExportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ExportError::Io(error) => Some(error),
+            ExportError::Render(error) => Some(error),
+            #[cfg(feature = "tiny_skia_renderer")]
+            ExportError::Image(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(error: io::Error) -> Self {
+        ExportError::Io(error)
+    }
+}
+
+impl From<RenderError> for ExportError {
+    fn from(error: RenderError) -> Self {
+        ExportError::Render(error)
+    }
+}
+
+#[cfg(feature = "tiny_skia_renderer")]
+impl From<image::ImageError> for ExportError {
+    fn from(error: image::ImageError) -> Self {
+        ExportError::Image(error)
+    }
+}
+
+/// A function that renders a [Canvas] and encodes it into bytes.
+pub type Exporter =
+    Box<dyn Fn(&Canvas, &ExportOptions) -> Result<Vec<u8>, ExportError> + Send + Sync>;
+
+/// A set of [Exporters](Exporter), keyed by file extension.
+///
+/// The [Default] registry contains every format enabled by the crate's features.
+/// Register your own to export with custom renderers.
+pub struct ExportRegistry {
+    exporters: HashMap<String, Exporter>,
+}
+
+impl ExportRegistry {
+    /// Create a registry without any exporters.
+    pub fn empty() -> Self {
+        Self {
+            exporters: HashMap::new(),
+        }
+    }
+
+    /// Register an exporter for a file extension, replacing any existing one.
+    ///
+    /// Extensions are matched case insensitively, and shouldn't include the leading `.`.
+    pub fn register<F>(&mut self, extension: &str, exporter: F)
+    where
+        F: Fn(&Canvas, &ExportOptions) -> Result<Vec<u8>, ExportError> + Send + Sync + 'static,
+    {
+        self.exporters
+            .insert(extension.to_ascii_lowercase(), Box::new(exporter));
+    }
+
+    /// Get every extension with a registered exporter.
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.exporters.keys().map(String::as_str)
+    }
+
+    /// Encode a [Canvas] in the format registered for `extension`.
+    pub fn encode(
+        &self,
+        canvas: &Canvas,
+        extension: &str,
+        options: &ExportOptions,
+    ) -> Result<Vec<u8>, ExportError> {
+        let extension = extension.to_ascii_lowercase();
+
+        match self.exporters.get(&extension) {
+            Some(exporter) => exporter(canvas, options),
+            None => Err(ExportError::UnsupportedFormat(extension)),
+        }
+    }
+
+    /// Export a [Canvas] to a file, picking the format from the path's extension.
+    ///
+    /// Nothing is written if encoding fails.
+    pub fn export<P: AsRef<Path>>(
+        &self,
+        canvas: &Canvas,
+        path: P,
+        options: &ExportOptions,
+    ) -> Result<(), ExportError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .ok_or(ExportError::MissingExtension)?;
+
+        let bytes = self.encode(canvas, extension, options)?;
+        fs::write(path, bytes)?;
+
+        Ok(())
+    }
+}
+
+impl Default for ExportRegistry {
+    #[allow(unused_mut)]
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        #[cfg(feature = "tiny_skia_renderer")]
+        {
+            use image::ImageOutputFormat;
+
+            registry.register("png", raster::encode_png);
+            registry.register("jpg", raster::encode_jpeg);
+            registry.register("jpeg", raster::encode_jpeg);
+            registry.register("webp", |canvas, options| {
+                raster::encode_with(canvas, options, ImageOutputFormat::WebP)
+            });
+            registry.register("tiff", |canvas, options| {
+                raster::encode_with(canvas, options, ImageOutputFormat::Tiff)
+            });
+            registry.register("tif", |canvas, options| {
+                raster::encode_with(canvas, options, ImageOutputFormat::Tiff)
+            });
+            registry.register("bmp", |canvas, options| {
+                raster::encode_with(canvas, options, ImageOutputFormat::Bmp)
+            });
+            registry.register("hdr", raster::encode_hdr);
+        }
+
+        #[cfg(feature = "svg_renderer")]
+        registry.register("svg", |canvas, options| {
+            use crate::renderers::SvgRenderer;
+
+            let renderer = match options.page {
+                Some(page) => SvgRenderer::from_page(
+                    page,
+                    options.background,
+                    false,
+                    options.preserve_height,
+                    32,
+                ),
+                None => SvgRenderer::new(
+                    options.size.as_vec2(),
+                    options.background,
+                    false,
+                    options.preserve_height,
+                    32,
+                ),
+            };
+
+            Ok(canvas.try_render(renderer)?.into_bytes())
+        });
+
+        registry
+    }
+}
+
+#[cfg(feature = "tiny_skia_renderer")]
+mod raster {
+    use std::io::Cursor;
+
+    use image::{
+        codecs::jpeg::{JpegEncoder, PixelDensity},
+        DynamicImage, ImageOutputFormat, RgbaImage,
+    };
+
+    use super::{ExportError, ExportOptions};
+    use crate::animation::png_error;
+    use crate::renderers::{Rgba32FSkiaRenderer, SkiaRenderer};
+    use crate::Canvas;
+
+    fn renderer(options: &ExportOptions) -> Result<SkiaRenderer, ExportError> {
+        Ok(SkiaRenderer::try_new(
+            options.size,
+            options.background,
+            options.antialias,
+            options.preserve_height,
+        )?)
+    }
+
+    fn render(canvas: &Canvas, options: &ExportOptions) -> Result<RgbaImage, ExportError> {
+        Ok(canvas.try_render(renderer(options)?)?)
+    }
+
+    /// Encode as PNG, including a `pHYs` chunk when a DPI is set.
+    pub fn encode_png(canvas: &Canvas, options: &ExportOptions) -> Result<Vec<u8>, ExportError> {
+        let image = render(canvas, options)?;
+
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, image.width(), image.height());
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        if let Some(dpi) = options.dpi {
+            let pixels_per_meter = (dpi / 0.0254).round() as u32;
+            encoder.set_pixel_dims(Some(png::PixelDimensions {
+                xppu: pixels_per_meter,
+                yppu: pixels_per_meter,
+                unit: png::Unit::Meter,
+            }));
+        }
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(image.as_raw()))
+            .map_err(png_error)?;
+
+        Ok(bytes)
+    }
+
+    /// Encode as JPEG at the configured quality, recording the DPI in the JFIF header.
+    pub fn encode_jpeg(canvas: &Canvas, options: &ExportOptions) -> Result<Vec<u8>, ExportError> {
+        let image = DynamicImage::ImageRgba8(render(canvas, options)?).to_rgb8();
+
+        let mut bytes = Vec::new();
+        let mut encoder = JpegEncoder::new_with_quality(&mut bytes, options.quality);
+        if let Some(dpi) = options.dpi {
+            encoder.set_pixel_density(PixelDensity::dpi(dpi.round() as u16));
+        }
+        encoder.encode_image(&image)?;
+
+        Ok(bytes)
+    }
+
+    /// Encode as Radiance HDR, without clamping colors.
+    pub fn encode_hdr(canvas: &Canvas, options: &ExportOptions) -> Result<Vec<u8>, ExportError> {
+        let image = canvas.render(Rgba32FSkiaRenderer::new(renderer(options)?));
+
+        let mut bytes = Vec::new();
+        Rgba32FSkiaRenderer::encode_hdr(&image, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Encode with one of the `image` crate's encoders.
+    pub fn encode_with(
+        canvas: &Canvas,
+        options: &ExportOptions,
+        format: ImageOutputFormat,
+    ) -> Result<Vec<u8>, ExportError> {
+        let mut bytes = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(render(canvas, options)?).write_to(&mut bytes, format)?;
+
+        Ok(bytes.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that formats are picked by extension, including custom ones, and unknown extensions are an error.
+    #[test]
+    fn picks_format_by_extension() {
+        let mut canvas = Canvas::default();
+        canvas.draw_circle((0.0, 0.0), 0.5, None, Some(Color::red()));
+
+        let registry = ExportRegistry::default();
+        let options = ExportOptions::new(UVec2::splat(16)).with_dpi(Some(300.0));
+
+        #[cfg(feature = "tiny_skia_renderer")]
+        {
+            let png = registry.encode(&canvas, "PNG", &options).unwrap();
+            assert!(png.starts_with(b"\x89PNG"));
+
+            // 300 DPI is 11811 pixels per meter.
+            let phys = png.windows(4).position(|w| w == b"pHYs").unwrap();
+            assert_eq!(&png[phys + 4..phys + 8], &11811u32.to_be_bytes());
+
+            let jpeg = registry.encode(&canvas, "jpg", &options).unwrap();
+            assert!(jpeg.starts_with(&[0xFF, 0xD8]));
+
+            assert!(matches!(
+                registry.encode(&canvas, "png", &ExportOptions::new(UVec2::ZERO)),
+                Err(ExportError::Render(RenderError::InvalidSize(_)))
+            ));
+        }
+
+        #[cfg(feature = "svg_renderer")]
+        {
+            assert!(registry
+                .encode(&canvas, "svg", &options)
+                .unwrap()
+                .starts_with(b"<svg"));
+
+            let page = crate::Page::paper(crate::PaperSize::A5);
+            let svg = registry
+                .encode(&canvas, "svg", &ExportOptions::default().with_page(page))
+                .unwrap();
+            assert!(String::from_utf8(svg).unwrap().contains("width=\"148mm\""));
+        }
+
+        assert!(matches!(
+            registry.encode(&canvas, "xyz", &options),
+            Err(ExportError::UnsupportedFormat(extension)) if extension == "xyz"
+        ));
+        assert!(matches!(
+            ExportRegistry::default().export(&canvas, "no_extension", &options),
+            Err(ExportError::MissingExtension)
+        ));
+
+        let mut custom = ExportRegistry::empty();
+        custom.register("txt", |canvas, _| {
+            Ok(canvas.as_raw().len().to_string().into_bytes())
+        });
+        assert_eq!(custom.encode(&canvas, "txt", &options).unwrap(), b"1");
+
+        let path = std::env::temp_dir().join(format!("barium-export-{}.txt", std::process::id()));
+        canvas.export_with(&custom, &path, &options).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"1");
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            canvas.export(&path, &options),
+            Err(ExportError::UnsupportedFormat(extension)) if extension == "txt"
+        ));
+    }
+}
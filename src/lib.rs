@@ -7,9 +7,44 @@
 #![deny(warnings)]
 #![deny(missing_docs)]
 
+/**
+ * Frame-by-frame animation.
+ *
+ * Renders a sequence of [Canvas] states through [SkiaRenderer](renderers::SkiaRenderer) and encodes them as an animated GIF or APNG.
+ * For longer animations, [FrameSequence](animation::FrameSequence) writes numbered frame files or raw RGBA for an external encoder.
+ */
+#[cfg(feature = "tiny_skia_renderer")]
+pub mod animation;
 mod canvas;
 mod color;
+mod curve;
+/**
+ * Writing canvases to files.
+ *
+ * [Canvas::export] renders and encodes a canvas in one step, picking the format from the file extension.
+ * Use an [ExportRegistry](export::ExportRegistry) directly to add formats backed by your own renderers.
+ */
+pub mod export;
+mod marker;
+mod measure;
+mod page;
+/**
+ * Ready-made colors and color schemes.
+ *
+ * Contains generators for harmonious palettes, continuous [Scales](palette::Scale) for mapping data to colors,
+ * and the full table of CSS named colors.
+ */
+pub mod palette;
 mod path_builder;
+mod pattern;
+/**
+ * Charts built out of plain shapes.
+ *
+ * A [Plot](plot::Plot) maps data onto a rectangle of World Space through an [AxisScale](plot::AxisScale) for each axis,
+ * and draws axes, gridlines and line, scatter, bar and area series. Tick and legend text is drawn as [Text].
+ * Scalar fields sampled on a [Grid](plot::Grid) can be drawn as heatmaps, or traced into contour lines and filled bands.
+ */
+pub mod plot;
 /**
  * A collection of backend renderers
  *
@@ -19,9 +54,26 @@ mod path_builder;
  * This module contains several basic renderers for everyday use. They also serve as referance if you want to implement your own renderer.
  */
 pub mod renderers;
+mod simplify;
+mod text;
+/**
+ * Easing and tweening utilities.
+ *
+ * The [Interpolate](tween::Interpolate) trait blends between two states of a value, from single numbers up to whole [Shapes](Shape).
+ * Combined with an [Easing](tween::Easing) curve, it makes animating between drawings straightforward.
+ */
+pub mod tween;
 
-pub use canvas::{Canvas, LineEnd, Renderer, Shape, Stroke};
-pub use color::Color;
-pub use glam::{Mat2, UVec2, Vec2};
+pub use canvas::{
+    Canvas, LineEnd, RenderError, Renderer, SceneInfo, Shape, Stroke, TryRenderer, Viewport,
+};
+pub use color::{Color, ColorParseError};
+pub use curve::{CubicBezier, Nurbs};
+pub use glam::{Affine2, Mat2, UVec2, Vec2};
 pub use image::RgbaImage;
+pub use marker::{Marker, Markers};
+pub use page::{Page, PaperSize, Unit};
 pub use path_builder::PathBuilder;
+pub use pattern::{Pattern, PatternTile, RepeatMode};
+pub use simplify::{Simplification, SimplifyReport};
+pub use text::Text;
@@ -0,0 +1,277 @@
+use std::f32::consts::TAU;
+
+use glam::Vec2;
+
+use crate::Shape;
+
+/// A shape drawn at a point along a stroked [Shape], like an arrowhead.
+///
+/// Markers are measured in stroke widths, and point along the outline of the shape.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Marker {
+    /// A filled triangle, with its tip just past the point so it covers the end of the stroke.
+    Arrow,
+    /// Two stroked lines meeting at the point.
+    OpenArrow,
+    /// A filled circle centered on the point.
+    Circle,
+    /// A filled square centered on the point.
+    Square,
+    /// A stroked line across the point.
+    Bar,
+    /// A filled polygon, in stroke widths. The point is at the origin, and the outline heads towards positive x.
+    Custom(Vec<Vec2>),
+}
+
+impl Marker {
+    /// Get the outline of the marker, in stroke widths.
+    pub(crate) fn outline(&self) -> Vec<Vec2> {
+        match self {
+            Marker::Arrow => vec![
+                Vec2::new(1.5, 0.0),
+                Vec2::new(-2.5, 2.0),
+                Vec2::new(-2.5, -2.0),
+                Vec2::new(1.5, 0.0),
+            ],
+            Marker::OpenArrow => vec![Vec2::new(-2.0, 2.0), Vec2::ZERO, Vec2::new(-2.0, -2.0)],
+            Marker::Circle => (0..=24)
+                .map(|i| {
+                    let angle = i as f32 / 24.0 * TAU;
+                    Vec2::new(angle.cos(), angle.sin()) * 1.5
+                })
+                .collect(),
+            Marker::Square => vec![
+                Vec2::new(-1.5, -1.5),
+                Vec2::new(1.5, -1.5),
+                Vec2::new(1.5, 1.5),
+                Vec2::new(-1.5, 1.5),
+                Vec2::new(-1.5, -1.5),
+            ],
+            Marker::Bar => vec![Vec2::new(0.0, -2.0), Vec2::new(0.0, 2.0)],
+            Marker::Custom(points) => {
+                let mut points = points.clone();
+                if points.len() > 2 && points[0] != points[points.len() - 1] {
+                    points.push(points[0]);
+                }
+                points
+            }
+        }
+    }
+
+    /// Check if the marker is drawn with the shape's stroke, rather than filled with its color.
+    pub(crate) fn is_stroked(&self) -> bool {
+        matches!(self, Marker::OpenArrow | Marker::Bar)
+    }
+}
+
+/// Where along a shape a marker goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Placement {
+    Start,
+    Middle,
+    End,
+}
+
+/// The [Markers](Marker) to draw at the start, middle and end of a stroked [Shape].
+///
+/// Markers scale with the width of the shape's stroke, and take on its color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Markers {
+    /// The marker at the first point, facing away from the rest of the shape.
+    pub start: Option<Marker>,
+    /// The marker halfway along the shape, facing the same way as it.
+    pub middle: Option<Marker>,
+    /// The marker at the last point, facing the same way as the shape.
+    pub end: Option<Marker>,
+    /// How much larger to draw every marker.
+    pub scale: f32,
+}
+
+impl Default for Markers {
+    fn default() -> Self {
+        Self {
+            start: None,
+            middle: None,
+            end: None,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Markers {
+    /// Create [Markers] with only a marker at the end, like a single arrow.
+    pub fn end(marker: Marker) -> Self {
+        Self::default().with_end(Some(marker))
+    }
+
+    /// Create [Markers] with the same marker at both ends, like a double-headed arrow.
+    pub fn both(marker: Marker) -> Self {
+        Self::default()
+            .with_start(Some(marker.clone()))
+            .with_end(Some(marker))
+    }
+
+    /// Modify the marker at the start, consuming the parent.
+    #[inline]
+    pub fn with_start(mut self, marker: Option<Marker>) -> Self {
+        self.start = marker;
+        self
+    }
+
+    /// Modify the marker in the middle, consuming the parent.
+    #[inline]
+    pub fn with_middle(mut self, marker: Option<Marker>) -> Self {
+        self.middle = marker;
+        self
+    }
+
+    /// Modify the marker at the end, consuming the parent.
+    #[inline]
+    pub fn with_end(mut self, marker: Option<Marker>) -> Self {
+        self.end = marker;
+        self
+    }
+
+    /// Modify how much larger to draw every marker, consuming the parent.
+    #[inline]
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub(crate) fn get(&self, placement: Placement) -> Option<&Marker> {
+        match placement {
+            Placement::Start => self.start.as_ref(),
+            Placement::Middle => self.middle.as_ref(),
+            Placement::End => self.end.as_ref(),
+        }
+    }
+
+    /// Get how far any marker reaches from its point, in stroke widths.
+    pub(crate) fn extent(&self) -> f32 {
+        [&self.start, &self.middle, &self.end]
+            .into_iter()
+            .flatten()
+            .flat_map(|marker| marker.outline())
+            .map(Vec2::length)
+            .fold(0.0, f32::max)
+            * self.scale
+            + 0.5
+    }
+}
+
+impl Shape {
+    /// Get a shape for each of the shape's [markers](Shape::markers), in the same space as its points.
+    ///
+    /// Shapes without a stroke have no markers. The markers of a shape with an [id](Shape::id) get ids made from it,
+    /// like `"line-marker-end"`, so they can be told apart from other shapes across keyframes.
+    pub fn marker_shapes(&self) -> Vec<Shape> {
+        [Placement::Start, Placement::Middle, Placement::End]
+            .into_iter()
+            .filter_map(|placement| self.marker_shape(placement))
+            .collect()
+    }
+
+    /// Get the shape of a single marker, if there is one at `placement`.
+    pub(crate) fn marker_shape(&self, placement: Placement) -> Option<Shape> {
+        let stroke = self.stroke?;
+        let markers = self.markers.as_ref()?;
+        let marker = markers.get(placement)?;
+
+        let length = self.length();
+        let (origin, direction) = match placement {
+            Placement::Start => (self.point_at(0.0)?, -self.tangent_at(0.0)?),
+            Placement::Middle => (self.point_at(length / 2.0)?, self.tangent_at(length / 2.0)?),
+            Placement::End => (self.point_at(length)?, self.tangent_at(length)?),
+        };
+
+        let scale = stroke.width * markers.scale;
+        let points = marker
+            .outline()
+            .into_iter()
+            .map(|point| origin + (direction * point.x + direction.perp() * point.y) * scale)
+            .collect();
+
+        let (stroke, fill) = if marker.is_stroked() {
+            (Some(stroke), None)
+        } else {
+            (None, Some(stroke.color))
+        };
+
+        let suffix = match placement {
+            Placement::Start => "start",
+            Placement::Middle => "middle",
+            Placement::End => "end",
+        };
+
+        Some(Shape {
+            points,
+            stroke,
+            fill,
+            pattern: None,
+            id: self
+                .id
+                .as_ref()
+                .map(|id| format!("{}-marker-{}", id, suffix)),
+            markers: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, LineEnd, Stroke};
+
+    /// Verify that markers sit at the ends and middle, face along the shape, scale with the stroke,
+    /// and take their ids from the shape.
+    #[test]
+    fn places_markers() {
+        let shape = Shape {
+            points: vec![Vec2::ZERO, Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0)],
+            stroke: Some(Stroke::new(Color::red(), 0.5, LineEnd::Round)),
+            fill: None,
+            pattern: None,
+            id: None,
+            markers: Some(
+                Markers::both(Marker::Arrow)
+                    .with_middle(Some(Marker::Bar))
+                    .with_scale(2.0),
+            ),
+        };
+
+        let markers = shape.marker_shapes();
+        assert_eq!(markers.len(), 3);
+
+        // The start arrow points backwards, away from the line.
+        assert_eq!(markers[0].points[0], Vec2::new(-1.5, 0.0));
+        assert_eq!(markers[0].fill, Some(Color::red()));
+        assert!(markers[0].is_polygon());
+
+        // The middle is the corner, where the bar still faces along the first segment.
+        assert_eq!(
+            markers[1].points,
+            vec![Vec2::new(2.0, -2.0), Vec2::new(2.0, 2.0)]
+        );
+        assert_eq!(markers[1].stroke, shape.stroke);
+
+        assert!(markers[2].points[0].abs_diff_eq(Vec2::new(2.0, 3.5), 0.0001));
+        assert_eq!(markers[2].id, None);
+
+        let mut named = shape.clone();
+        named.id = Some("line".to_string());
+        let ids: Vec<_> = named
+            .marker_shapes()
+            .into_iter()
+            .map(|marker| marker.id.unwrap())
+            .collect();
+        assert_eq!(
+            ids,
+            ["line-marker-start", "line-marker-middle", "line-marker-end"]
+        );
+
+        let mut unstroked = shape;
+        unstroked.stroke = None;
+        assert!(unstroked.marker_shapes().is_empty());
+    }
+}
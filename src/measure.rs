@@ -0,0 +1,333 @@
+use glam::Vec2;
+
+use crate::{PathBuilder, Shape};
+
+impl Shape {
+    /// Get the total length of the shape's outline, in the same units as the points.
+    pub fn length(&self) -> f32 {
+        length(&self.points)
+    }
+
+    /// Get the point `distance` along the outline from the first point.
+    ///
+    /// The distance is clamped to the length of the shape. Returns `None` if the shape has no points.
+    pub fn point_at(&self, distance: f32) -> Option<Vec2> {
+        point_at(&self.points, distance)
+    }
+
+    /// Get the direction the outline is heading in `distance` along it from the first point, as a unit vector.
+    ///
+    /// Returns `None` if every point of the shape is in the same place.
+    pub fn tangent_at(&self, distance: f32) -> Option<Vec2> {
+        tangent_at(&self.points, distance)
+    }
+
+    /// Replace the points with evenly spaced ones, that are no further than `spacing` apart along the outline.
+    ///
+    /// The first and last points are kept in place, so polygons stay closed. Corners between them are cut.
+    pub fn resample(&mut self, spacing: f32) {
+        self.points = resample_spacing(&self.points, spacing);
+    }
+
+    /// Cut the outline down to the part between the `start` and `end` fractions of its length.
+    ///
+    /// Trimming from `0` to a growing `end` draws the shape on. Polygons are no longer closed after trimming,
+    /// and nothing is left if `start` isn't before `end`.
+    pub fn trim(&mut self, start: f32, end: f32) {
+        let total = self.length();
+        self.points = trim(
+            &self.points,
+            start.clamp(0.0, 1.0) * total,
+            end.clamp(0.0, 1.0) * total,
+        );
+    }
+}
+
+impl PathBuilder {
+    /// Get the total length of the path so far, not counting moves of the "pen".
+    pub fn length(&self) -> f32 {
+        self.subpaths().map(length).sum()
+    }
+
+    /// Get the point `distance` along the path from where it started, not counting moves of the "pen".
+    ///
+    /// The distance is clamped to the length of the path.
+    pub fn point_at(&self, distance: f32) -> Vec2 {
+        let (subpath, distance) = self.locate(distance);
+        point_at(subpath, distance).expect("Subpaths always have a point")
+    }
+
+    /// Get the direction the path is heading in `distance` along it, as a unit vector.
+    ///
+    /// Returns `None` if nothing has been drawn yet.
+    pub fn tangent_at(&self, distance: f32) -> Option<Vec2> {
+        let (subpath, distance) = self.locate(distance);
+        tangent_at(subpath, distance)
+    }
+
+    /// Replace the points of each part of the path with evenly spaced ones, no further than `spacing` apart.
+    ///
+    /// See [Shape::resample].
+    pub fn resample(self, spacing: f32) -> Self {
+        self.map_subpaths(|subpaths| {
+            subpaths
+                .iter()
+                .map(|subpath| resample_spacing(subpath, spacing))
+                .collect()
+        })
+    }
+
+    /// Cut the path down to the part between the `start` and `end` fractions of its length.
+    ///
+    /// See [Shape::trim]. Parts of the path that are cut away completely are removed.
+    pub fn trim(self, start: f32, end: f32) -> Self {
+        let total = self.length();
+        let (start, end) = (start.clamp(0.0, 1.0) * total, end.clamp(0.0, 1.0) * total);
+
+        self.map_subpaths(|subpaths| {
+            let mut offset = 0.0;
+            let mut trimmed = Vec::new();
+
+            for subpath in subpaths {
+                let length = length(subpath);
+                let subpath = trim(subpath, start - offset, end - offset);
+                if subpath.len() > 1 {
+                    trimmed.push(subpath);
+                }
+                offset += length;
+            }
+
+            trimmed
+        })
+    }
+
+    /// Find the part of the path that `distance` falls in, and how far along that part it is.
+    fn locate(&self, distance: f32) -> (&[Vec2], f32) {
+        let mut remaining = distance.max(0.0);
+        let mut last = None;
+
+        for subpath in self.subpaths() {
+            let length = length(subpath);
+            if remaining <= length && length > 0.0 {
+                return (subpath, remaining);
+            }
+            remaining -= length;
+            last = Some((subpath, length));
+        }
+
+        last.unwrap_or((&[Vec2::ZERO], 0.0))
+    }
+}
+
+/// Get the total length of a polyline.
+fn length(points: &[Vec2]) -> f32 {
+    points
+        .windows(2)
+        .map(|pair| pair[0].distance(pair[1]))
+        .sum()
+}
+
+/// Find the segment `distance` along a polyline falls on, and how far along that segment it is from `0` to `1`.
+///
+/// Segments without any length are skipped, unless they are all that there is.
+fn locate(points: &[Vec2], distance: f32) -> Option<(usize, f32)> {
+    let mut remaining = distance.max(0.0);
+    let mut last = None;
+
+    for (index, pair) in points.windows(2).enumerate() {
+        let length = pair[0].distance(pair[1]);
+        if length == 0.0 {
+            continue;
+        }
+
+        if remaining <= length {
+            return Some((index, remaining / length));
+        }
+        remaining -= length;
+        last = Some((index, 1.0));
+    }
+
+    last
+}
+
+fn point_at(points: &[Vec2], distance: f32) -> Option<Vec2> {
+    match locate(points, distance) {
+        Some((index, t)) => Some(points[index].lerp(points[index + 1], t)),
+        None => points.first().copied(),
+    }
+}
+
+fn tangent_at(points: &[Vec2], distance: f32) -> Option<Vec2> {
+    locate(points, distance).map(|(index, _)| (points[index + 1] - points[index]).normalize())
+}
+
+/// Resample a polyline to `count` points, evenly spaced along its length.
+///
+/// The first and last points are kept in place, so closed polygons stay closed.
+pub(crate) fn resample(points: &[Vec2], count: usize) -> Vec<Vec2> {
+    if points.len() < 2 || count < 2 {
+        return points.iter().copied().cycle().take(count).collect();
+    }
+
+    let mut distances = Vec::with_capacity(points.len());
+    let mut total = 0.0;
+    distances.push(0.0);
+    for pair in points.windows(2) {
+        total += pair[0].distance(pair[1]);
+        distances.push(total);
+    }
+
+    let mut resampled = Vec::with_capacity(count);
+    let mut segment = 0;
+    for i in 0..count {
+        let target = total * i as f32 / (count - 1) as f32;
+
+        while segment < points.len() - 2 && distances[segment + 1] < target {
+            segment += 1;
+        }
+
+        let length = distances[segment + 1] - distances[segment];
+        let t = if length > 0.0 {
+            ((target - distances[segment]) / length).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        resampled.push(points[segment].lerp(points[segment + 1], t));
+    }
+
+    resampled
+}
+
+/// Resample a polyline to as few evenly spaced points as keeps them no further than `spacing` apart.
+fn resample_spacing(points: &[Vec2], spacing: f32) -> Vec<Vec2> {
+    if points.len() < 2 || spacing.is_nan() || spacing <= 0.0 {
+        return points.to_vec();
+    }
+
+    let count = (length(points) / spacing).ceil() as usize + 1;
+    resample(points, count.max(2))
+}
+
+/// Cut a polyline down to the part between `start` and `end` along its length.
+fn trim(points: &[Vec2], start: f32, end: f32) -> Vec<Vec2> {
+    let total = length(points);
+    let (start, end) = (start.max(0.0), end.min(total));
+
+    if start >= end {
+        return Vec::new();
+    }
+
+    let mut trimmed = Vec::new();
+    let mut travelled = 0.0;
+
+    for pair in points.windows(2) {
+        let length = pair[0].distance(pair[1]);
+        let (from, to) = (travelled, travelled + length);
+        travelled = to;
+
+        if to <= start || length == 0.0 {
+            continue;
+        }
+        if trimmed.is_empty() {
+            trimmed.push(pair[0].lerp(pair[1], (start - from) / length));
+        }
+        if to >= end {
+            trimmed.push(pair[0].lerp(pair[1], (end - from) / length));
+            break;
+        }
+        trimmed.push(pair[1]);
+    }
+
+    trimmed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 0.0001;
+
+    fn square() -> Shape {
+        Shape {
+            points: vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(0.0, 1.0),
+                Vec2::new(0.0, 0.0),
+            ],
+            stroke: None,
+            fill: None,
+            pattern: None,
+            id: None,
+            markers: None,
+        }
+    }
+
+    /// Verify the length, points and tangents along a shape, including past either end.
+    #[test]
+    fn measures_shapes() {
+        let shape = square();
+
+        assert_eq!(shape.length(), 4.0);
+        assert_eq!(shape.point_at(1.5), Some(Vec2::new(1.0, 0.5)));
+        assert_eq!(shape.point_at(-1.0), Some(Vec2::ZERO));
+        assert_eq!(shape.point_at(10.0), Some(Vec2::ZERO));
+        assert_eq!(shape.tangent_at(2.5), Some(Vec2::new(-1.0, 0.0)));
+        assert_eq!(shape.tangent_at(10.0), Some(Vec2::new(0.0, -1.0)));
+
+        let mut dot = square();
+        dot.points = vec![Vec2::ONE; 3];
+        assert_eq!(dot.point_at(1.0), Some(Vec2::ONE));
+        assert_eq!(dot.tangent_at(1.0), None);
+    }
+
+    /// Verify that resampling spaces points evenly, and that trimming keeps the right part of the outline.
+    #[test]
+    fn resamples_and_trims() {
+        let mut shape = square();
+        shape.resample(0.3);
+
+        assert_eq!(shape.points.len(), 15);
+        assert!(shape.is_polygon());
+        for pair in shape.points.windows(2) {
+            assert!(pair[0].distance(pair[1]) <= 0.3);
+        }
+
+        let mut shape = square();
+        shape.trim(0.125, 0.625);
+        assert_eq!(
+            shape.points,
+            vec![
+                Vec2::new(0.5, 0.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(0.5, 1.0),
+            ]
+        );
+        assert!((shape.length() - 2.0).abs() < EPSILON);
+
+        let mut shape = square();
+        shape.trim(0.5, 0.5);
+        assert!(!shape.is_drawable());
+    }
+
+    /// Verify that paths are measured and trimmed across moves of the "pen".
+    #[test]
+    fn measures_paths() {
+        let path = PathBuilder::new(100)
+            .line_to((2.0, 0.0))
+            .move_to((0.0, 1.0))
+            .line_to((0.0, 3.0));
+
+        assert_eq!(path.length(), 4.0);
+        assert_eq!(path.point_at(3.0), Vec2::new(0.0, 2.0));
+        assert_eq!(path.tangent_at(1.0), Some(Vec2::X));
+        assert_eq!(path.tangent_at(3.0), Some(Vec2::Y));
+
+        let trimmed = path.trim(0.75, 1.0);
+        assert_eq!(trimmed.length(), 1.0);
+        assert_eq!(trimmed.point_at(0.0), Vec2::new(0.0, 2.0));
+    }
+}
@@ -0,0 +1,162 @@
+use glam::{UVec2, Vec2};
+
+/// A unit of physical length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Pixels. Their physical size depends on the [Page]'s DPI.
+    Px,
+    /// Millimeters.
+    Mm,
+    /// Centimeters.
+    Cm,
+    /// Inches.
+    In,
+    /// Typographic points, `1/72` of an inch.
+    Pt,
+}
+
+impl Unit {
+    /// Get how many of this unit fit in an inch, at a given DPI.
+    pub fn per_inch(&self, dpi: f32) -> f32 {
+        match self {
+            Unit::Px => dpi,
+            Unit::Mm => 25.4,
+            Unit::Cm => 2.54,
+            Unit::In => 1.0,
+            Unit::Pt => 72.0,
+        }
+    }
+
+    /// Get the suffix used for this unit by CSS and SVG.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Unit::Px => "px",
+            Unit::Mm => "mm",
+            Unit::Cm => "cm",
+            Unit::In => "in",
+            Unit::Pt => "pt",
+        }
+    }
+}
+
+/// Standard paper sizes, in portrait orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperSize {
+    /// ISO A3, 297mm × 420mm.
+    A3,
+    /// ISO A4, 210mm × 297mm.
+    A4,
+    /// ISO A5, 148mm × 210mm.
+    A5,
+    /// US Letter, 8.5in × 11in.
+    Letter,
+    /// US Legal, 8.5in × 14in.
+    Legal,
+    /// US Tabloid, 11in × 17in.
+    Tabloid,
+}
+
+/// The physical size of an output, along with the resolution it is rasterized at.
+///
+/// Renderers that work in pixels use [pixel_size](Self::pixel_size),
+/// while vector renderers can keep the physical [size](Self::size) and [unit](Self::unit).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Page {
+    size: Vec2,
+    unit: Unit,
+    dpi: f32,
+}
+
+impl Page {
+    /// The DPI used unless otherwise specified. This is what CSS considers a pixel.
+    pub const DEFAULT_DPI: f32 = 96.0;
+
+    /// Create a new [Page] that is `size` large, measured in `unit`.
+    pub fn new<S: Into<Vec2>>(size: S, unit: Unit) -> Self {
+        Self {
+            size: size.into(),
+            unit,
+            dpi: Self::DEFAULT_DPI,
+        }
+    }
+
+    /// Create a new [Page] measured in pixels.
+    pub fn pixels(size: UVec2) -> Self {
+        Self::new(size.as_vec2(), Unit::Px)
+    }
+
+    /// Create a new [Page] of a standard paper size, in portrait orientation.
+    pub fn paper(paper: PaperSize) -> Self {
+        match paper {
+            PaperSize::A3 => Self::new((297.0, 420.0), Unit::Mm),
+            PaperSize::A4 => Self::new((210.0, 297.0), Unit::Mm),
+            PaperSize::A5 => Self::new((148.0, 210.0), Unit::Mm),
+            PaperSize::Letter => Self::new((8.5, 11.0), Unit::In),
+            PaperSize::Legal => Self::new((8.5, 14.0), Unit::In),
+            PaperSize::Tabloid => Self::new((11.0, 17.0), Unit::In),
+        }
+    }
+
+    /// Modify the resolution used when rasterizing, consuming the parent.
+    ///
+    /// A page measured in pixels keeps its pixel size, and changes physical size instead.
+    #[inline]
+    pub fn with_dpi(mut self, dpi: f32) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// Turn the page sideways, so it is wider than it is tall, consuming the parent.
+    #[inline]
+    pub fn landscape(mut self) -> Self {
+        if self.size.y > self.size.x {
+            self.size = Vec2::new(self.size.y, self.size.x);
+        }
+        self
+    }
+
+    /// Get the size of the page, in its own [unit](Self::unit).
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    /// Get the unit the page is measured in.
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// Get the resolution used when rasterizing, in dots per inch.
+    pub fn dpi(&self) -> f32 {
+        self.dpi
+    }
+
+    /// Get the size of the page in another unit.
+    pub fn size_in(&self, unit: Unit) -> Vec2 {
+        self.size / self.unit.per_inch(self.dpi) * unit.per_inch(self.dpi)
+    }
+
+    /// Get the size of the page in pixels at its DPI, rounded to the nearest pixel.
+    pub fn pixel_size(&self) -> UVec2 {
+        self.size_in(Unit::Px).round().max(Vec2::ONE).as_uvec2()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that paper sizes convert to the expected pixel sizes.
+    #[test]
+    fn paper_pixel_sizes() {
+        let a4 = Page::paper(PaperSize::A4).with_dpi(300.0);
+        assert_eq!(a4.pixel_size(), UVec2::new(2480, 3508));
+        assert_eq!(a4.landscape().pixel_size(), UVec2::new(3508, 2480));
+
+        let letter = Page::paper(PaperSize::Letter).with_dpi(72.0);
+        assert_eq!(letter.size_in(Unit::Pt), Vec2::new(612.0, 792.0));
+
+        let pixels = Page::pixels(UVec2::new(960, 480));
+        assert_eq!(pixels.pixel_size(), UVec2::new(960, 480));
+        assert_eq!(pixels.size_in(Unit::In), Vec2::new(10.0, 5.0));
+    }
+}
@@ -0,0 +1,406 @@
+use crate::Color;
+
+/// Every named color from [CSS Color Module Level 4](https://www.w3.org/TR/css-color-4/#named-colors), as `0xRRGGBB`.
+///
+/// Sorted by name, so it can be binary searched.
+pub const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+/// Look up a CSS named color. The name must be lowercase.
+pub(crate) fn named_color(name: &str) -> Option<Color> {
+    let index = NAMED_COLORS
+        .binary_search_by(|(candidate, _)| candidate.cmp(&name))
+        .ok()?;
+
+    let hex = NAMED_COLORS[index].1;
+    Some(Color::new(
+        (hex >> 16 & 0xFF) as f32 / 255.0,
+        (hex >> 8 & 0xFF) as f32 / 255.0,
+        (hex & 0xFF) as f32 / 255.0,
+        1.0,
+    ))
+}
+
+/// Colors whose hues sit next to `base` on the color wheel.
+///
+/// The `count` colors are spread evenly across `spread` turns of hue, centered on `base`.
+/// Hues are rotated in OkLCH, so every color keeps the perceived lightness of `base`.
+pub fn analogous(base: Color, count: usize, spread: f32) -> Vec<Color> {
+    if count <= 1 {
+        return vec![base; count];
+    }
+
+    (0..count)
+        .map(|i| rotate_hue(base, spread * (i as f32 / (count - 1) as f32 - 0.5)))
+        .collect()
+}
+
+/// `base` and the color opposite it on the color wheel.
+pub fn complementary(base: Color) -> [Color; 2] {
+    [base, rotate_hue(base, 0.5)]
+}
+
+/// `base` and the two colors a third of the color wheel away from it.
+pub fn triadic(base: Color) -> [Color; 3] {
+    [
+        base,
+        rotate_hue(base, 1.0 / 3.0),
+        rotate_hue(base, 2.0 / 3.0),
+    ]
+}
+
+/// `count` colors with evenly spaced hues and the same OkLCH `lightness` and `chroma`.
+///
+/// Useful for categorical data, since no color stands out more than another.
+/// Hues that can't reach `chroma` within sRGB have their chroma reduced.
+pub fn even_hues(count: usize, lightness: f32, chroma: f32) -> Vec<Color> {
+    (0..count)
+        .map(|i| oklch_in_gamut(lightness, chroma, i as f32 / count as f32))
+        .collect()
+}
+
+/// Rotate the OkLCH hue of a color by some number of turns.
+fn rotate_hue(color: Color, turns: f32) -> Color {
+    let (lightness, chroma, hue, alpha) = color.to_oklch();
+    oklch_in_gamut(lightness, chroma, hue + turns).with_a(alpha)
+}
+
+/// Create a color from OkLCH values, reducing the chroma until it fits within sRGB.
+fn oklch_in_gamut(lightness: f32, chroma: f32, hue: f32) -> Color {
+    const EPSILON: f32 = 0.0001;
+
+    let in_gamut = |color: Color| {
+        [color.r(), color.g(), color.b()]
+            .iter()
+            .all(|channel| (-EPSILON..=1.0 + EPSILON).contains(channel))
+    };
+
+    let mut color = Color::from_oklch(lightness, chroma, hue, 1.0);
+
+    if !in_gamut(color) {
+        let (mut low, mut high) = (0.0, chroma);
+        for _ in 0..24 {
+            let middle = (low + high) / 2.0;
+            if in_gamut(Color::from_oklch(lightness, middle, hue, 1.0)) {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        color = Color::from_oklch(lightness, low, hue, 1.0);
+    }
+
+    Color::new(
+        color.r().clamp(0.0, 1.0),
+        color.g().clamp(0.0, 1.0),
+        color.b().clamp(0.0, 1.0),
+        1.0,
+    )
+}
+
+/// A continuous color scale, for mapping numbers to colors.
+///
+/// The scale is made of evenly spaced stops, and colors between them are mixed in Oklab.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scale {
+    stops: Vec<Color>,
+}
+
+impl Scale {
+    /// Create a scale from evenly spaced stops.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stops` is empty.
+    pub fn new(stops: Vec<Color>) -> Self {
+        assert!(!stops.is_empty(), "A scale needs at least one stop");
+        Self { stops }
+    }
+
+    /// A sequential scale from `low` to `high`.
+    pub fn sequential(low: Color, high: Color) -> Self {
+        Self::new(vec![low, high])
+    }
+
+    /// A diverging scale from `low`, through `middle`, to `high`.
+    pub fn diverging(low: Color, middle: Color, high: Color) -> Self {
+        Self::new(vec![low, middle, high])
+    }
+
+    /// The perceptually uniform viridis scale, from dark purple to yellow.
+    pub fn viridis() -> Self {
+        Self::from_hex_stops(&[
+            0x440154, 0x472D7B, 0x3B528B, 0x2C728E, 0x21908C, 0x27AD81, 0x5DC863, 0xAADC32,
+            0xFDE725,
+        ])
+    }
+
+    /// The perceptually uniform magma scale, from black to pale yellow.
+    pub fn magma() -> Self {
+        Self::from_hex_stops(&[
+            0x000004, 0x1D1147, 0x51127C, 0x822681, 0xB63679, 0xE65164, 0xFB8861, 0xFEC287,
+            0xFCFDBF,
+        ])
+    }
+
+    /// The red to blue diverging scale from ColorBrewer, through a light grey.
+    pub fn red_blue() -> Self {
+        Self::from_hex_stops(&[
+            0xB2182B, 0xEF8A62, 0xFDDBC7, 0xF7F7F7, 0xD1E5F0, 0x67A9CF, 0x2166AC,
+        ])
+    }
+
+    fn from_hex_stops(stops: &[u32]) -> Self {
+        Self::new(
+            stops
+                .iter()
+                .map(|hex| {
+                    Color::new(
+                        (hex >> 16 & 0xFF) as f32 / 255.0,
+                        (hex >> 8 & 0xFF) as f32 / 255.0,
+                        (hex & 0xFF) as f32 / 255.0,
+                        1.0,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the stops of the scale.
+    pub fn stops(&self) -> &[Color] {
+        &self.stops
+    }
+
+    /// Get the color at `t`, where `0.0` is the first stop and `1.0` is the last.
+    ///
+    /// `t` is clamped to `0.0..=1.0`.
+    pub fn sample(&self, t: f32) -> Color {
+        let position = t.clamp(0.0, 1.0) * (self.stops.len() - 1) as f32;
+        let index = (position as usize).min(self.stops.len().saturating_sub(2));
+
+        let fraction = position - index as f32;
+
+        match self.stops.get(index + 1) {
+            Some(next) if fraction >= 1.0 => *next,
+            Some(next) if fraction > 0.0 => self.stops[index].mix_oklab(next, fraction),
+            _ => self.stops[index],
+        }
+    }
+
+    /// Sample `count` evenly spaced colors, including both ends of the scale.
+    pub fn colors(&self, count: usize) -> Vec<Color> {
+        match count {
+            0 => Vec::new(),
+            1 => vec![self.sample(0.5)],
+            _ => (0..count)
+                .map(|i| self.sample(i as f32 / (count - 1) as f32))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that the named color table is sorted, so lookups find every entry.
+    #[test]
+    fn named_colors_are_sorted() {
+        for window in NAMED_COLORS.windows(2) {
+            assert!(window[0].0 < window[1].0, "{} is out of order", window[1].0);
+        }
+
+        for (name, _) in NAMED_COLORS {
+            assert!(named_color(name).is_some());
+        }
+
+        assert_eq!(
+            named_color("rebeccapurple"),
+            Some(Color::from_hex("663399").unwrap())
+        );
+        assert_eq!(named_color("notacolor"), None);
+    }
+
+    /// Verify that generated palettes keep lightness and spread their hues evenly.
+    #[test]
+    fn generators_rotate_hue() {
+        let base = Color::new(0.8, 0.3, 0.2, 1.0);
+        let (lightness, _, hue, _) = base.to_oklch();
+
+        let [_, complement] = complementary(base);
+        let (complement_lightness, _, complement_hue, _) = complement.to_oklch();
+        assert!((complement_lightness - lightness).abs() < 0.01);
+        assert!(((complement_hue - hue).rem_euclid(1.0) - 0.5).abs() < 0.01);
+
+        let colors = analogous(base, 3, 0.2);
+        assert!(colors[1].delta_e_ok(&base) < 0.001);
+
+        let hues = even_hues(6, 0.7, 0.1);
+        for (i, color) in hues.iter().enumerate() {
+            let (l, c, h, _) = color.to_oklch();
+            assert!((l - 0.7).abs() < 0.01);
+            assert!((c - 0.1).abs() < 0.01);
+            assert!((h - i as f32 / 6.0).abs() < 0.01);
+        }
+    }
+
+    /// Verify that scales hit their stops exactly and clamp outside of them.
+    #[test]
+    fn scale_sampling() {
+        let viridis = Scale::viridis();
+        assert_eq!(viridis.sample(0.0), Color::from_hex("440154").unwrap());
+        assert_eq!(viridis.sample(2.0), Color::from_hex("FDE725").unwrap());
+        assert_eq!(viridis.colors(9).len(), 9);
+
+        let scale = Scale::diverging(Color::red(), Color::white(), Color::blue());
+        assert!(scale.sample(0.5).delta_e_ok(&Color::white()) < 0.001);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{Canvas, Color, Stroke};
+use crate::curve::{catmull_rom, CatmullRom};
+use crate::{Canvas, Color, CubicBezier, Nurbs, Stroke};
 use glam::Vec2;
 
 /// A builder to describe the shape of a path.
@@ -94,6 +95,129 @@ impl PathBuilder {
         self
     }
 
+    /// Draw a smooth curve that passes through each of the points in turn, starting from the "pen".
+    ///
+    /// `tension` ranges from `0`, a Catmull-Rom spline, to `1`, which draws straight lines between the points.
+    /// Points that are unevenly spaced can make the curve overshoot, see
+    /// [centripetal_catmull_rom_through](Self::centripetal_catmull_rom_through) to avoid that.
+    pub fn catmull_rom_through<P: Into<Vec2>, I: IntoIterator<Item = P>>(
+        self,
+        points: I,
+        tension: f32,
+    ) -> Self {
+        self.catmull_rom(points, tension, CatmullRom::Uniform)
+    }
+
+    /// Draw a smooth curve that passes through each of the points in turn, starting from the "pen".
+    ///
+    /// Like [catmull_rom_through](Self::catmull_rom_through), but spaces the points by the square root of the distance between them,
+    /// which keeps the curve from looping or forming cusps between points that are close together.
+    pub fn centripetal_catmull_rom_through<P: Into<Vec2>, I: IntoIterator<Item = P>>(
+        self,
+        points: I,
+        tension: f32,
+    ) -> Self {
+        self.catmull_rom(points, tension, CatmullRom::Centripetal)
+    }
+
+    /// Draw a uniform cubic B-spline, using the "pen" as the first control point.
+    ///
+    /// The curve ends at the last control point, but is only pulled towards the others.
+    pub fn b_spline_to<P: Into<Vec2>, I: IntoIterator<Item = P>>(self, control_points: I) -> Self {
+        let mut points = vec![self.current_point()];
+        points.extend(control_points.into_iter().map(Into::into));
+
+        match Nurbs::b_spline(points) {
+            Some(spline) => self.nurbs(&spline),
+            None => self,
+        }
+    }
+
+    /// Draw a [Nurbs] curve, with a straight line from the "pen" to where it starts.
+    pub fn nurbs(mut self, curve: &Nurbs) -> Self {
+        let (start, end) = curve.domain();
+
+        let curve_length: f32 = curve
+            .control_points()
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum();
+        let point_count = (curve_length * self.points_per_unit as f32).max(1.0) as usize;
+
+        for i in 0..=point_count {
+            let t = start + (end - start) * i as f32 / point_count as f32;
+            self = self.line_to(curve.point(t));
+        }
+
+        self
+    }
+
+    /// Draw a few cubic bezier curves that stay within `tolerance` of every point of a dense polyline, starting from the "pen".
+    ///
+    /// See [CubicBezier::fit].
+    pub fn fit_through<P: Into<Vec2>, I: IntoIterator<Item = P>>(
+        mut self,
+        points: I,
+        tolerance: f32,
+    ) -> Self {
+        let mut path = vec![self.current_point()];
+        path.extend(points.into_iter().map(Into::into));
+
+        // Samples along a curve can stop short of its end, so each curve finishes with a line to it.
+        for curve in CubicBezier::fit(&path, tolerance) {
+            self = self
+                .cubic_bezier_to(curve.end, curve.control_0, curve.control_1)
+                .line_to(curve.end);
+        }
+
+        self
+    }
+
+    fn catmull_rom<P: Into<Vec2>, I: IntoIterator<Item = P>>(
+        mut self,
+        points: I,
+        tension: f32,
+        kind: CatmullRom,
+    ) -> Self {
+        let mut path = vec![self.current_point()];
+        path.extend(points.into_iter().map(Into::into));
+
+        // Samples along a curve can stop short of its end, so each curve finishes with a line to the point it passes through.
+        for curve in catmull_rom(&path, tension, kind) {
+            self = self
+                .cubic_bezier_to(curve.end, curve.control_0, curve.control_1)
+                .line_to(curve.end);
+        }
+
+        self
+    }
+
+    /// Iterate over each part of the path, split wherever the "pen" was moved.
+    pub(crate) fn subpaths(&self) -> impl Iterator<Item = &[Vec2]> {
+        self.shapes
+            .iter()
+            .chain(std::iter::once(&self.current_shape))
+            .map(Vec::as_slice)
+    }
+
+    /// Replace the parts of the path, keeping the "pen" where it is if no parts are left.
+    pub(crate) fn map_subpaths<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&[Vec<Vec2>]) -> Vec<Vec<Vec2>>,
+    {
+        let pen = self.current_point();
+        self.shapes.push(self.current_shape);
+
+        self.shapes = f(&self.shapes);
+        self.current_shape = self.shapes.pop().unwrap_or_else(|| vec![pen]);
+
+        self
+    }
+
+    fn current_point(&self) -> Vec2 {
+        self.current_shape[self.current_shape.len() - 1]
+    }
+
     /// Get the first point in the path.
     pub fn first_point(&self) -> Vec2 {
         if let Some(first) = self.shapes.first() {
@@ -184,3 +308,36 @@ impl PathBuilder {
         Self::point_on_line(d, e, t)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that splines pass through every point they are given, even when their segments are too short to sample.
+    #[test]
+    fn splines_reach_their_points() {
+        let points = [
+            Vec2::new(0.3, 0.0),
+            Vec2::new(0.7, 0.2),
+            Vec2::new(1.5, -0.4),
+        ];
+
+        for path in [
+            PathBuilder::new(1).catmull_rom_through(points, 0.0),
+            PathBuilder::new(1).centripetal_catmull_rom_through(points, 0.5),
+            PathBuilder::new(7).catmull_rom_through(points, 0.0),
+        ] {
+            let path = path.subpaths().next().unwrap().to_vec();
+            for point in points {
+                assert!(path.contains(&point), "{:?} is not on {:?}", point, path);
+            }
+        }
+
+        let dense: Vec<Vec2> = (1..=20)
+            .map(|n| Vec2::new(n as f32 * 0.05, (n as f32 * 0.3).sin() * 0.2))
+            .collect();
+        let path = PathBuilder::new(3).fit_through(dense.iter().copied(), 0.01);
+        let path = path.subpaths().next().unwrap();
+        assert_eq!(path.last(), dense.last());
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use glam::{Affine2, Vec2};
+use image::RgbaImage;
+
+use crate::Canvas;
+
+/// A paint that tiles an image or a sub-drawing inside a [Shape](crate::Shape).
+///
+/// A single tile occupies the rectangle from `(0, 0)` to [size](Self::size) in "Pattern Space".
+/// The [transform](Self::transform) maps Pattern Space onto the space of the shape it is attached to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    /// The contents of a single tile.
+    pub tile: PatternTile,
+    /// The size of a single tile, in Pattern Space.
+    pub size: Vec2,
+    /// Transform from Pattern Space to the space of the shape.
+    pub transform: Affine2,
+    /// How the tile is repeated outside of its bounds.
+    pub repeat: RepeatMode,
+}
+
+impl Pattern {
+    /// Create a [Pattern] that tiles an image.
+    ///
+    /// The image is stretched to fill a tile of `size`.
+    pub fn image<S: Into<Vec2>>(image: RgbaImage, size: S) -> Self {
+        Self {
+            tile: PatternTile::Image(Arc::new(image)),
+            size: size.into(),
+            transform: Affine2::IDENTITY,
+            repeat: RepeatMode::Repeat,
+        }
+    }
+
+    /// Create a [Pattern] that tiles a nested [Canvas].
+    ///
+    /// The canvas is drawn from the perspective of its camera, as if it were rendered to an image of `size`
+    /// with the height preserved.
+    pub fn canvas<S: Into<Vec2>>(canvas: Canvas, size: S) -> Self {
+        Self {
+            tile: PatternTile::Canvas(Arc::new(canvas)),
+            size: size.into(),
+            transform: Affine2::IDENTITY,
+            repeat: RepeatMode::Repeat,
+        }
+    }
+
+    /// Modify the tile transform, consuming the parent.
+    #[inline]
+    pub fn with_transform(mut self, transform: Affine2) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Modify the repeat mode, consuming the parent.
+    #[inline]
+    pub fn with_repeat(mut self, repeat: RepeatMode) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Get the transform from the pixels of a tile of `resolution` (origin in the top left, y down) to the space of the shape.
+    pub(crate) fn tile_pixel_transform(&self, resolution: Vec2) -> Affine2 {
+        self.transform
+            * Affine2::from_mat2_translation(
+                glam::Mat2::from_diagonal(Vec2::new(
+                    self.size.x / resolution.x,
+                    -self.size.y / resolution.y,
+                )),
+                Vec2::new(0.0, self.size.y),
+            )
+    }
+}
+
+/// The contents of a single [Pattern] tile.
+///
+/// Both variants are reference counted, so cloning a [Pattern] is cheap.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternTile {
+    /// A raster image.
+    Image(Arc<RgbaImage>),
+    /// A nested drawing.
+    Canvas(Arc<Canvas>),
+}
+
+/// How a [Pattern] continues outside of a single tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatMode {
+    /// Repeat the tile horizontally and vertically.
+    Repeat,
+    /// Repeat the tile horizontally and vertically, mirroring every other tile so adjacent edges meet.
+    Reflect,
+    /// Extend the edges of the tile outward.
+    ///
+    /// [SvgRenderer](crate::renderers::SvgRenderer) cannot express this, so it draws a single tile instead.
+    Pad,
+}
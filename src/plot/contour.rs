@@ -0,0 +1,743 @@
+use std::collections::HashMap;
+
+use glam::Vec2;
+
+use super::{Plot, Swatch};
+use crate::{palette::Scale, Stroke};
+
+/// A rectangular grid of samples of a 2D scalar field.
+///
+/// Samples are stored row by row, starting from the bottom left, and are spread evenly between the grid's bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    values: Vec<f32>,
+    min: Vec2,
+    max: Vec2,
+}
+
+impl Grid {
+    /// Create a new [Grid] from `width * height` values, row by row, starting from the bottom left.
+    ///
+    /// The samples are placed at whole numbers, from `(0, 0)` to `(width - 1, height - 1)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values doesn't match the size of the grid.
+    pub fn new(width: usize, height: usize, values: Vec<f32>) -> Self {
+        assert_eq!(
+            values.len(),
+            width * height,
+            "A grid needs a value for every sample"
+        );
+
+        Self {
+            width,
+            height,
+            values,
+            min: Vec2::ZERO,
+            max: Vec2::new(
+                width.saturating_sub(1) as f32,
+                height.saturating_sub(1) as f32,
+            ),
+        }
+    }
+
+    /// Create a new [Grid] by calling `f` with the column and row of each sample.
+    pub fn from_fn<F: FnMut(usize, usize) -> f32>(width: usize, height: usize, mut f: F) -> Self {
+        let values = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+
+        Self::new(width, height, values)
+    }
+
+    /// Modify where the first and last samples are placed, consuming the parent.
+    #[inline]
+    pub fn with_bounds<P: Into<Vec2>>(mut self, min: P, max: P) -> Self {
+        self.min = min.into();
+        self.max = max.into();
+        self
+    }
+
+    /// Get the number of columns and rows of samples.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Get the value of the sample in `column` and `row`.
+    pub fn get(&self, column: usize, row: usize) -> f32 {
+        self.values[row * self.width + column]
+    }
+
+    /// Get where the sample in `column` and `row` is placed.
+    pub fn position(&self, column: usize, row: usize) -> Vec2 {
+        self.position_at(Vec2::new(column as f32, row as f32))
+    }
+
+    /// Get the smallest and largest finite values, or `None` if there are none.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.values
+            .iter()
+            .copied()
+            .filter(|value| value.is_finite())
+            .fold(None, |range, value| match range {
+                Some((min, max)) => Some((value.min(min), value.max(max))),
+                None => Some((value, value)),
+            })
+    }
+
+    /// Trace the lines where the field crosses `level`.
+    ///
+    /// Lines are closed along the edge of the grid, so each one has its last point the same as its first,
+    /// unless it runs into a sample that isn't finite.
+    /// Cells where each pair of opposite corners is on the same side of the level are resolved by the average of the corners.
+    pub fn iso_lines(&self, level: f32) -> Vec<Vec<Vec2>> {
+        if self.width == 0 || self.height == 0 {
+            return Vec::new();
+        }
+
+        let mut segments = Vec::new();
+
+        for row in 0..=self.height {
+            for column in 0..=self.width {
+                let corners = match self.padded_corners(column, row) {
+                    Some(corners) => corners,
+                    None => continue,
+                };
+                let inside = corners.map(|(_, value)| value >= level);
+
+                let crossings: Vec<usize> = (0..4)
+                    .filter(|edge| inside[*edge] != inside[(edge + 1) % 4])
+                    .collect();
+
+                let pairs = if crossings.len() == 4 {
+                    // A saddle: cut off the corners on the other side of the level from the center.
+                    let center = corners.iter().map(|(_, value)| value).sum::<f32>() / 4.0;
+                    let isolated = center < level;
+                    (0..4)
+                        .filter(|corner| inside[*corner] == isolated)
+                        .map(|corner| ((corner + 3) % 4, corner))
+                        .collect()
+                } else if crossings.len() == 2 {
+                    vec![(crossings[0], crossings[1])]
+                } else {
+                    Vec::new()
+                };
+
+                for (a, b) in pairs {
+                    segments.push([
+                        crossing(column, row, &corners, a, level),
+                        crossing(column, row, &corners, b, level),
+                    ]);
+                }
+            }
+        }
+
+        let mut lines = join_segments(segments);
+        // The cells in the corners of the border cross the level twice at the same point.
+        for line in &mut lines {
+            line.dedup();
+        }
+        lines
+    }
+
+    /// Find the regions where the field is at least `low`, but below `high`, as closed polygons.
+    ///
+    /// Each polygon is its outer ring followed by the rings of any holes in it, which run the other way round.
+    /// Polygons are closed along the edge of the grid. Saddles are resolved the same way as [iso_lines](Self::iso_lines),
+    /// so the bands line up with the lines.
+    pub fn iso_bands(&self, low: f32, high: f32) -> Vec<Vec<Vec<Vec2>>> {
+        let mut pieces = Vec::new();
+
+        for (column, row) in self.cells() {
+            let corners = self.corners(column, row);
+            let center = corners.iter().map(|(_, value)| value).sum::<f32>() / 4.0;
+
+            let offsets = [(0, 0), (1, 0), (1, 1), (0, 1)];
+            let corners: Vec<_> = corners
+                .iter()
+                .zip(offsets)
+                .map(|((point, value), (x, y))| {
+                    (*point, *value, ((column + x) * 2, (row + y) * 2, 0))
+                })
+                .collect();
+
+            for polygon in clip(&corners, low, true, center) {
+                pieces.extend(clip(&polygon, high, false, center));
+            }
+        }
+
+        // Flipped bounds turn the cells clockwise.
+        let extent = self.max - self.min;
+        merge_pieces(pieces, (extent.x * extent.y).signum())
+    }
+
+    /// Iterate over the bottom left sample of each cell with finite values in all four corners.
+    fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height.saturating_sub(1))
+            .flat_map(move |row| (0..self.width.saturating_sub(1)).map(move |column| (column, row)))
+            .filter(move |(column, row)| {
+                self.corners(*column, *row)
+                    .iter()
+                    .all(|(_, value)| value.is_finite())
+            })
+    }
+
+    /// Get the positions and values of a cell's corners, counter-clockwise from the bottom left.
+    fn corners(&self, column: usize, row: usize) -> [(Vec2, f32); 4] {
+        [(0, 0), (1, 0), (1, 1), (0, 1)].map(|(x, y)| {
+            (
+                self.position(column + x, row + y),
+                self.get(column + x, row + y),
+            )
+        })
+    }
+
+    /// Get the corners of a cell like [corners](Self::corners), but of the grid surrounded by a border of samples
+    /// that are below every level, placed on the edge of the grid, or `None` if any of the grid's samples aren't finite.
+    ///
+    /// `column` and `row` count from the bottom left of the border.
+    fn padded_corners(&self, column: usize, row: usize) -> Option<[(Vec2, f32); 4]> {
+        let mut corners = [(Vec2::ZERO, 0.0); 4];
+
+        for (corner, (x, y)) in [(0, 0), (1, 0), (1, 1), (0, 1)].into_iter().enumerate() {
+            let (column, row) = (column + x, row + y);
+            let inner = (
+                column.clamp(1, self.width) - 1,
+                row.clamp(1, self.height) - 1,
+            );
+
+            let value = if (column, row) == (inner.0 + 1, inner.1 + 1) {
+                let value = self.get(inner.0, inner.1);
+                if !value.is_finite() {
+                    return None;
+                }
+                value
+            } else {
+                f32::NEG_INFINITY
+            };
+
+            corners[corner] = (self.position(inner.0, inner.1), value);
+        }
+
+        Some(corners)
+    }
+
+    fn position_at(&self, sample: Vec2) -> Vec2 {
+        let steps = Vec2::new(
+            self.width.saturating_sub(1).max(1) as f32,
+            self.height.saturating_sub(1).max(1) as f32,
+        );
+        self.min + (self.max - self.min) * sample / steps
+    }
+}
+
+/// Where a corner of a band's outline lies on the grid, so the pieces of neighbouring cells can be matched up.
+///
+/// Positions are numbered like the keys of [crossing], along with `0` for a sample, `1` for a crossing of the lower threshold,
+/// or `2` for a crossing of the upper one.
+type Key = (usize, usize, u8);
+
+/// Clip a convex polygon, with a value at each corner, to where the value is at least `threshold` if `above`,
+/// otherwise where it is below `threshold`.
+///
+/// When the polygon is crossed four times, the kept parts are joined through the middle if `center` is kept, otherwise they are separate.
+fn clip(
+    polygon: &[(Vec2, f32, Key)],
+    threshold: f32,
+    above: bool,
+    center: f32,
+) -> Vec<Vec<(Vec2, f32, Key)>> {
+    let keep = |value: f32| (value >= threshold) == above;
+    let tag = if above { 1 } else { 2 };
+
+    // Start outside, so every kept run of the outline begins and ends at a crossing.
+    let start = match polygon.iter().position(|(_, value, _)| !keep(*value)) {
+        Some(start) => start,
+        None => return vec![polygon.to_vec()],
+    };
+
+    let mut runs = Vec::new();
+    let mut current = Vec::new();
+    for offset in 0..polygon.len() {
+        let (point, value, key) = polygon[(start + offset) % polygon.len()];
+        let (next, next_value, next_key) = polygon[(start + offset + 1) % polygon.len()];
+
+        if keep(value) {
+            current.push((point, value, key));
+        }
+
+        if keep(value) != keep(next_value) {
+            // Only edges along the grid are crossed, since the rest already run along a threshold.
+            // Between two samples the edge is halfway, otherwise it is the edge of the earlier crossing.
+            let edge = if key.2 == 0 && next_key.2 == 0 {
+                ((key.0 + next_key.0) / 2, (key.1 + next_key.1) / 2)
+            } else if key.2 != 0 {
+                (key.0, key.1)
+            } else {
+                (next_key.0, next_key.1)
+            };
+
+            let t = (threshold - value) / (next_value - value);
+            current.push((point.lerp(next, t), threshold, (edge.0, edge.1, tag)));
+
+            if keep(value) {
+                runs.push(std::mem::take(&mut current));
+            }
+        }
+    }
+
+    if runs.len() > 1 && !keep(center) {
+        runs
+    } else if runs.is_empty() {
+        Vec::new()
+    } else {
+        vec![runs.concat()]
+    }
+}
+
+/// Merge the pieces of a band from every cell into polygons, each an outer ring followed by its holes.
+///
+/// Pieces all run the same way round, so the edges between neighbouring pieces run both ways and cancel out,
+/// leaving only the outlines. Rings that run the same way as the cells, given by the sign of `orientation`, are outer rings.
+fn merge_pieces(pieces: Vec<Vec<(Vec2, f32, Key)>>, orientation: f32) -> Vec<Vec<Vec<Vec2>>> {
+    let mut positions = HashMap::new();
+    let mut edges: Vec<(Key, Key)> = Vec::new();
+    let mut alive = Vec::new();
+    let mut indices: HashMap<(Key, Key), usize> = HashMap::new();
+
+    for piece in pieces.iter().filter(|piece| piece.len() > 2) {
+        for (index, (point, _, key)) in piece.iter().enumerate() {
+            positions.entry(*key).or_insert(*point);
+
+            let next = piece[(index + 1) % piece.len()].2;
+            match indices.get(&(next, *key)) {
+                Some(reverse) if alive[*reverse] => alive[*reverse] = false,
+                _ => {
+                    indices.insert((*key, next), edges.len());
+                    edges.push((*key, next));
+                    alive.push(true);
+                }
+            }
+        }
+    }
+
+    let mut outgoing: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (index, (from, _)) in edges.iter().enumerate() {
+        if alive[index] {
+            outgoing.entry(*from).or_default().push(index);
+        }
+    }
+
+    // Walk each ring of edges. Every point has as many edges leaving it as arriving, so each walk ends where it started.
+    let mut rings = Vec::new();
+    for first in 0..edges.len() {
+        if !alive[first] {
+            continue;
+        }
+        alive[first] = false;
+
+        let (start, mut key) = edges[first];
+        let mut ring = vec![positions[&start]];
+        while key != start {
+            ring.push(positions[&key]);
+            let next = outgoing[&key]
+                .iter()
+                .copied()
+                .find(|index| alive[*index])
+                .expect("every point of an outline has an edge leaving it");
+            alive[next] = false;
+            key = edges[next].1;
+        }
+        ring.push(ring[0]);
+
+        rings.push((signed_area(&ring) * orientation, ring));
+    }
+
+    let (outers, holes): (Vec<_>, Vec<_>) = rings
+        .into_iter()
+        .filter(|(area, _)| *area != 0.0)
+        .partition(|(area, _)| *area > 0.0);
+    let mut polygons: Vec<Vec<Vec<Vec2>>> =
+        outers.iter().map(|(_, ring)| vec![ring.clone()]).collect();
+
+    // Each hole belongs to the smallest outer ring around it. The middle of an edge can't be on another ring,
+    // since the edges that rings share have cancelled out.
+    for (_, hole) in holes {
+        let probe = hole[0].lerp(hole[1], 0.5);
+        let owner = outers
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, ring))| contains(ring, probe))
+            .min_by(|(_, (a, _)), (_, (b, _))| a.total_cmp(b))
+            .map(|(index, _)| index);
+
+        if let Some(owner) = owner {
+            polygons[owner].push(hole);
+        }
+    }
+
+    polygons
+}
+
+/// Get the area inside a closed ring, positive if it runs counter-clockwise.
+fn signed_area(ring: &[Vec2]) -> f32 {
+    ring.windows(2)
+        .map(|pair| pair[0].perp_dot(pair[1]))
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Check if a point is inside a closed ring.
+fn contains(ring: &[Vec2], point: Vec2) -> bool {
+    ring.windows(2)
+        .filter(|pair| (pair[0].y > point.y) != (pair[1].y > point.y))
+        .filter(|pair| {
+            let t = (point.y - pair[0].y) / (pair[1].y - pair[0].y);
+            point.x < pair[0].x + (pair[1].x - pair[0].x) * t
+        })
+        .count()
+        % 2
+        == 1
+}
+
+/// Get where the field crosses `level` along the edge from `corners[edge]` to the next corner of the cell in `column` and `row`,
+/// keyed by which edge of the grid it is on.
+fn crossing(
+    column: usize,
+    row: usize,
+    corners: &[(Vec2, f32); 4],
+    edge: usize,
+    level: f32,
+) -> ((usize, usize), Vec2) {
+    // Always interpolate from the bottom or left end, so neighbouring cells find exactly the same point.
+    let (a, b) = if edge < 2 {
+        (corners[edge], corners[edge + 1])
+    } else {
+        (corners[(edge + 1) % 4], corners[edge])
+    };
+
+    // Edges are numbered on a grid twice as fine as the samples, so each one has its own key.
+    let key = match edge {
+        0 => (column * 2 + 1, row * 2),
+        1 => (column * 2 + 2, row * 2 + 1),
+        2 => (column * 2 + 1, row * 2 + 2),
+        _ => (column * 2, row * 2 + 1),
+    };
+
+    // Edges out to the border have both ends in the same place, and an infinite value at one of them.
+    if a.0 == b.0 {
+        return (key, a.0);
+    }
+
+    let t = (level - a.1) / (b.1 - a.1);
+    (key, a.0.lerp(b.0, t))
+}
+
+/// Join segments that share an end into polylines.
+fn join_segments(segments: Vec<[((usize, usize), Vec2); 2]>) -> Vec<Vec<Vec2>> {
+    let mut ends: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (index, segment) in segments.iter().enumerate() {
+        for (key, _) in segment {
+            ends.entry(*key).or_default().push(index);
+        }
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut lines = Vec::new();
+
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+
+        let mut keys = vec![segments[start][0].0, segments[start][1].0];
+        let mut points = vec![segments[start][0].1, segments[start][1].1];
+
+        // Follow the line forwards, then reverse it and follow it the other way.
+        for _ in 0..2 {
+            loop {
+                let key = keys[keys.len() - 1];
+                let next = ends[&key].iter().copied().find(|index| !used[*index]);
+
+                let next = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+                used[next] = true;
+
+                let far = if segments[next][0].0 == key { 1 } else { 0 };
+                keys.push(segments[next][far].0);
+                points.push(segments[next][far].1);
+            }
+
+            keys.reverse();
+            points.reverse();
+        }
+
+        lines.push(points);
+    }
+
+    lines
+}
+
+impl Plot {
+    /// Fill a cell around each sample of a grid, colored by where its value falls in `range` along a [Scale].
+    ///
+    /// The grid's bounds are in data. Samples that aren't finite are left empty.
+    pub fn heatmap(&mut self, grid: &Grid, scale: &Scale, range: (f32, f32)) {
+        let (width, height) = grid.size();
+        let span = range.1 - range.0;
+
+        for row in 0..height {
+            for column in 0..width {
+                let value = grid.get(column, row);
+                if !value.is_finite() {
+                    continue;
+                }
+
+                let sample = Vec2::new(column as f32, row as f32);
+                let min = self.to_world(grid.position_at(sample - 0.5));
+                let max = self.to_world(grid.position_at(sample + 0.5));
+                let t = if span != 0.0 {
+                    (value - range.0) / span
+                } else {
+                    0.5
+                };
+
+                self.push(
+                    vec![
+                        min,
+                        Vec2::new(max.x, min.y),
+                        max,
+                        Vec2::new(min.x, max.y),
+                        min,
+                    ],
+                    None,
+                    Some(scale.sample(t)),
+                );
+            }
+        }
+
+        self.last_swatch = None;
+    }
+
+    /// Draw the [iso_lines](Grid::iso_lines) of a grid at each of the levels.
+    pub fn contour_lines(&mut self, grid: &Grid, levels: &[f32], stroke: Stroke) {
+        for level in levels {
+            for line in grid.iso_lines(*level) {
+                let points = line.into_iter().map(|point| self.to_world(point)).collect();
+                self.push(points, Some(stroke), None);
+            }
+        }
+
+        self.last_swatch = Some(Swatch::Line(stroke));
+    }
+
+    /// Fill the [iso_bands](Grid::iso_bands) of a grid between each pair of neighbouring thresholds,
+    /// colored evenly along a [Scale] from the lowest band to the highest.
+    pub fn contour_bands(&mut self, grid: &Grid, thresholds: &[f32], scale: &Scale) {
+        let colors = scale.colors(thresholds.len().saturating_sub(1));
+
+        for (pair, color) in thresholds.windows(2).zip(colors) {
+            for polygon in grid.iso_bands(pair[0], pair[1]) {
+                // Holes run the other way round, so joining each of them onto the start of the outer ring
+                // and back again leaves them unfilled.
+                let start = polygon[0][0];
+                let mut points = polygon[0].clone();
+                for hole in &polygon[1..] {
+                    points.extend(hole);
+                    points.push(start);
+                }
+
+                let points = points
+                    .into_iter()
+                    .map(|point| self.to_world(point))
+                    .collect();
+                self.push(points, None, Some(color));
+            }
+        }
+
+        self.last_swatch = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Get the area inside a polygon, less the area of its holes.
+    fn area(polygon: &[Vec<Vec2>]) -> f32 {
+        polygon.iter().map(|ring| signed_area(ring)).sum()
+    }
+
+    /// Verify that a peak produces a single closed ring, and lines leaving the grid are closed along its edge.
+    #[test]
+    fn traces_iso_lines() {
+        let peak = Grid::from_fn(9, 9, |x, y| {
+            let offset = Vec2::new(x as f32 - 4.0, y as f32 - 4.0);
+            10.0 - offset.length()
+        })
+        .with_bounds((-1.0, -1.0), (1.0, 1.0));
+
+        let lines = peak.iso_lines(7.5);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0][0], lines[0][lines[0].len() - 1]);
+        for point in &lines[0] {
+            assert!((point.length() - 0.625).abs() < 0.05);
+        }
+
+        let slope = Grid::from_fn(4, 3, |x, _| x as f32);
+        let lines = slope.iso_lines(1.5);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0][0], lines[0][lines[0].len() - 1]);
+        for corner in [(1.5, 0.0), (1.5, 2.0), (3.0, 2.0), (3.0, 0.0)] {
+            assert!(lines[0].contains(&Vec2::from(corner)));
+        }
+        assert!((signed_area(&lines[0]).abs() - 3.0).abs() < 0.0001);
+    }
+
+    /// Verify that a peak cut off by the edge of the grid is closed along the edge.
+    #[test]
+    fn closes_lines_at_edge() {
+        let peak = Grid::from_fn(9, 9, |x, y| {
+            let offset = Vec2::new(x as f32 - 8.0, y as f32 - 4.0);
+            10.0 - offset.length()
+        });
+
+        let lines = peak.iso_lines(7.5);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0][0], lines[0][lines[0].len() - 1]);
+
+        let center = Vec2::new(8.0, 4.0);
+        for point in &lines[0] {
+            assert!(point.x == 8.0 || (point.distance(center) - 2.5).abs() < 0.2);
+        }
+        assert!(lines[0].contains(&Vec2::new(8.0, 1.5)));
+        assert!(lines[0].contains(&Vec2::new(8.0, 6.5)));
+
+        // Half of a circle, a little smaller where the cells cut across it.
+        let half_circle = std::f32::consts::PI * 2.5 * 2.5 / 2.0;
+        assert!((signed_area(&lines[0]).abs() - half_circle).abs() < 0.5);
+    }
+
+    /// Verify that saddles are joined or split by the average of the corners, the same way for lines and bands.
+    #[test]
+    fn resolves_saddles() {
+        // High corners at the bottom left and top right, with an average of zero.
+        let saddle = Grid::new(2, 2, vec![2.0, -2.0, -2.0, 2.0]);
+
+        for (level, pieces) in [(0.5, 2), (-0.5, 1)] {
+            let lines = saddle.iso_lines(level);
+            let bands = saddle.iso_bands(level, 10.0);
+
+            assert_eq!(lines.len(), pieces);
+            assert_eq!(bands.len(), pieces);
+
+            // Every line runs along the outline of a band.
+            for point in lines.iter().flatten() {
+                assert!(bands
+                    .iter()
+                    .flatten()
+                    .flatten()
+                    .any(|corner| corner.abs_diff_eq(*point, 0.0001)));
+            }
+        }
+    }
+
+    /// Verify that bands are closed along the edge of the grid, and together cover all of it.
+    #[test]
+    fn bands_cover_grid() {
+        let grid = Grid::from_fn(6, 5, |x, y| ((x * 7 + y * 3) % 5) as f32);
+        let thresholds = [-1.0, 1.0, 2.5, 10.0];
+
+        let total: f32 = thresholds
+            .windows(2)
+            .flat_map(|pair| grid.iso_bands(pair[0], pair[1]))
+            .inspect(|polygon| {
+                for ring in polygon {
+                    assert_eq!(ring[0], ring[ring.len() - 1]);
+                }
+            })
+            .map(|polygon| area(&polygon))
+            .sum();
+
+        assert!((total - 20.0).abs() < 0.001);
+    }
+
+    /// Verify that the pieces of a band are merged across cells into outer rings with holes.
+    #[test]
+    fn merges_bands() {
+        let peak = Grid::from_fn(9, 9, |x, y| {
+            let offset = Vec2::new(x as f32 - 4.0, y as f32 - 4.0);
+            10.0 - offset.length()
+        });
+
+        let disc = peak.iso_bands(8.5, 11.0);
+        assert_eq!(disc.len(), 1);
+        assert_eq!(disc[0].len(), 1);
+
+        let ring = peak.iso_bands(7.5, 8.5);
+        assert_eq!(ring.len(), 1);
+        assert_eq!(ring[0].len(), 2);
+        assert!(signed_area(&ring[0][0]) > 0.0);
+        assert!(signed_area(&ring[0][1]) < 0.0);
+
+        let outside = peak.iso_bands(-100.0, 7.5);
+        assert_eq!(outside.len(), 1);
+        assert_eq!(outside[0].len(), 2);
+        assert!((signed_area(&outside[0][0]) - 64.0).abs() < 0.001);
+
+        // Flipping the bounds turns everything around, but still finds the outer rings.
+        let flipped = peak.with_bounds((8.0, 0.0), (0.0, 8.0)).iso_bands(7.5, 8.5);
+        assert_eq!(flipped.len(), 1);
+        assert_eq!(flipped[0].len(), 2);
+    }
+
+    /// Verify that a band with several holes is filled as a single shape that leaves every hole empty.
+    #[test]
+    fn fills_bands_with_holes() {
+        let peaks = Grid::from_fn(12, 6, |x, y| {
+            let y = y as f32 - 2.5;
+            [2.5, 8.5]
+                .iter()
+                .map(|center| 4.0 - ((x as f32 - center).powi(2) + y * y).sqrt())
+                .fold(0.0, f32::max)
+        });
+
+        let bands = peaks.iso_bands(-10.0, 2.0);
+        assert_eq!(bands.len(), 1);
+        assert_eq!(bands[0].len(), 3);
+
+        let scale = Scale::sequential(crate::Color::black(), crate::Color::white());
+        let mut plot = Plot::new((0.0, 0.0), (11.0, 5.0), (0.0, 11.0), (0.0, 5.0));
+        plot.contour_bands(&peaks, &[-10.0, 2.0], &scale);
+
+        let shapes = plot.into_shapes();
+        assert_eq!(shapes.len(), 1);
+        assert!(shapes[0].is_polygon());
+        assert!((signed_area(&shapes[0].points) - area(&bands[0])).abs() < 0.001);
+        assert!(area(&bands[0]) < 50.0);
+    }
+
+    /// Verify that a heatmap fills a cell around each finite sample, colored along the scale.
+    #[test]
+    fn draws_heatmap() {
+        let grid = Grid::new(2, 2, vec![0.0, 1.0, f32::NAN, 2.0]);
+        let scale = Scale::sequential(crate::Color::black(), crate::Color::white());
+
+        let mut plot = Plot::new((0.0, 0.0), (2.0, 2.0), (-0.5, 1.5), (-0.5, 1.5));
+        plot.heatmap(&grid, &scale, (0.0, 2.0));
+
+        let shapes = plot.into_shapes();
+        assert_eq!(shapes.len(), 3);
+        assert_eq!(shapes[0].points[0], Vec2::ZERO);
+        assert_eq!(shapes[0].points[2], Vec2::ONE);
+        assert_eq!(shapes[0].fill, Some(scale.sample(0.0)));
+        assert_eq!(shapes[2].fill, Some(scale.sample(1.0)));
+    }
+}
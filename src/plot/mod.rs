@@ -0,0 +1,704 @@
+use glam::Vec2;
+
+use crate::{Canvas, Color, LineEnd, Marker, Shape, Stroke, Text};
+
+mod contour;
+
+pub use contour::Grid;
+
+/// How an [AxisScale] spaces out values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mapping {
+    /// Equal differences between values are equal distances apart.
+    Linear,
+    /// Equal ratios between values are equal distances apart. Values must be positive.
+    Log,
+}
+
+/// Maps values from data to positions along one axis of World Space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisScale {
+    domain: (f32, f32),
+    range: (f32, f32),
+    mapping: Mapping,
+}
+
+/// A value on an axis worth marking, usually a round number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tick {
+    /// The value in data.
+    pub value: f32,
+    /// Where the value is along the axis, in World Space.
+    pub position: f32,
+    /// The value, formatted with as many decimal places as the ticks need to tell them apart.
+    pub label: String,
+}
+
+impl AxisScale {
+    /// Create a scale that maps `domain` in data linearly onto `range` in World Space.
+    pub fn linear(domain: (f32, f32), range: (f32, f32)) -> Self {
+        Self {
+            domain,
+            range,
+            mapping: Mapping::Linear,
+        }
+    }
+
+    /// Create a scale that maps `domain` in data logarithmically onto `range` in World Space.
+    ///
+    /// Values that aren't positive are treated as the smallest positive number.
+    pub fn log(domain: (f32, f32), range: (f32, f32)) -> Self {
+        Self {
+            domain,
+            range,
+            mapping: Mapping::Log,
+        }
+    }
+
+    /// Get the values at either end of the scale.
+    pub fn domain(&self) -> (f32, f32) {
+        self.domain
+    }
+
+    /// Get the positions in World Space at either end of the scale.
+    pub fn range(&self) -> (f32, f32) {
+        self.range
+    }
+
+    /// Get how the scale spaces out values.
+    pub fn mapping(&self) -> Mapping {
+        self.mapping
+    }
+
+    /// Map a value in data to a position in World Space.
+    ///
+    /// Values outside the domain map to positions outside the range.
+    pub fn map(&self, value: f32) -> f32 {
+        let (start, end) = (self.transform(self.domain.0), self.transform(self.domain.1));
+        let t = if end != start {
+            (self.transform(value) - start) / (end - start)
+        } else {
+            0.5
+        };
+
+        self.range.0 + (self.range.1 - self.range.0) * t
+    }
+
+    /// Map a position in World Space back to a value in data.
+    pub fn invert(&self, position: f32) -> f32 {
+        let (start, end) = (self.transform(self.domain.0), self.transform(self.domain.1));
+        let t = if self.range.1 != self.range.0 {
+            (position - self.range.0) / (self.range.1 - self.range.0)
+        } else {
+            0.5
+        };
+
+        let value = start + (end - start) * t;
+        match self.mapping {
+            Mapping::Linear => value,
+            Mapping::Log => 10f32.powf(value),
+        }
+    }
+
+    /// Pick about `count` round values inside the domain to mark.
+    ///
+    /// Linear ticks are spaced by 1, 2 or 5 times a power of ten. Log ticks are powers of ten,
+    /// along with 2 and 5 times them when the domain covers too few powers of ten.
+    pub fn ticks(&self, count: usize) -> Vec<Tick> {
+        let (low, high) = if self.domain.0 <= self.domain.1 {
+            self.domain
+        } else {
+            (self.domain.1, self.domain.0)
+        };
+
+        let (values, decimals) = match self.mapping {
+            Mapping::Linear => {
+                let step = nice_step(high - low, count);
+                if !step.is_finite() || step <= 0.0 {
+                    return Vec::new();
+                }
+
+                let first = (low / step).ceil() as i64;
+                let last = (high / step).floor() as i64;
+                let decimals = (-step.log10().floor()).max(0.0) as usize;
+
+                ((first..=last).map(|i| i as f32 * step).collect(), decimals)
+            }
+            Mapping::Log => {
+                let (low, high) = (low.max(f32::MIN_POSITIVE), high.max(f32::MIN_POSITIVE));
+                let (first, last) = (low.log10().floor() as i32, high.log10().ceil() as i32);
+
+                let powers = (first..=last)
+                    .map(|power| 10f32.powi(power))
+                    .filter(|value| *value >= low * 0.9999 && *value <= high * 1.0001)
+                    .count();
+
+                let multiples: &[f32] = if powers < count {
+                    &[1.0, 2.0, 5.0]
+                } else {
+                    &[1.0]
+                };
+
+                let values: Vec<f32> = (first..=last)
+                    .flat_map(|power| {
+                        multiples
+                            .iter()
+                            .map(move |multiple| multiple * 10f32.powi(power))
+                    })
+                    .filter(|value| *value >= low * 0.9999 && *value <= high * 1.0001)
+                    .collect();
+
+                (values, (-first).max(0) as usize)
+            }
+        };
+
+        values
+            .into_iter()
+            .map(|value| Tick {
+                value,
+                position: self.map(value),
+                label: format_tick(value, decimals),
+            })
+            .collect()
+    }
+
+    fn transform(&self, value: f32) -> f32 {
+        match self.mapping {
+            Mapping::Linear => value,
+            Mapping::Log => value.max(f32::MIN_POSITIVE).log10(),
+        }
+    }
+}
+
+/// Round `span / count` to 1, 2 or 5 times a power of ten.
+fn nice_step(span: f32, count: usize) -> f32 {
+    let raw = span.abs() / count.max(1) as f32;
+    let magnitude = 10f32.powf(raw.log10().floor());
+
+    let nice = match raw / magnitude {
+        residual if residual <= 1.0 => 1.0,
+        residual if residual <= 2.0 => 2.0,
+        residual if residual <= 5.0 => 5.0,
+        _ => 10.0,
+    };
+
+    nice * magnitude
+}
+
+/// Format a tick's value, writing negative zero as `0`.
+fn format_tick(value: f32, decimals: usize) -> String {
+    let label = format!("{:.*}", decimals, value);
+    if label
+        .trim_start_matches('-')
+        .trim_matches(|c| c == '0' || c == '.')
+        .is_empty()
+    {
+        label.trim_start_matches('-').to_string()
+    } else {
+        label
+    }
+}
+
+/// What a series looks like in a legend.
+#[derive(Debug, Clone, PartialEq)]
+enum Swatch {
+    Line(Stroke),
+    Marker(Marker, f32, Color),
+    Fill(Color),
+}
+
+/// A chart, built out of plain [Shapes](Shape) in World Space.
+///
+/// Data is placed inside a rectangle of World Space by a scale for each axis.
+/// Series aren't clipped to the rectangle, so data outside of the domains is drawn outside of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plot {
+    x: AxisScale,
+    y: AxisScale,
+    tick_count: usize,
+    text_height: f32,
+    text_color: Color,
+    shapes: Vec<Shape>,
+    legend: Vec<(String, Swatch)>,
+    last_swatch: Option<Swatch>,
+}
+
+impl Plot {
+    /// Create a new [Plot] that maps `x_domain` and `y_domain` in data onto the rectangle from `min` to `max` in World Space.
+    pub fn new<P: Into<Vec2>>(min: P, max: P, x_domain: (f32, f32), y_domain: (f32, f32)) -> Self {
+        let (min, max) = (min.into(), max.into());
+
+        Self {
+            x: AxisScale::linear(x_domain, (min.x, max.x)),
+            y: AxisScale::linear(y_domain, (min.y, max.y)),
+            tick_count: 5,
+            text_height: (max.y - min.y).abs() / 25.0,
+            text_color: Color::black(),
+            shapes: Vec::new(),
+            legend: Vec::new(),
+            last_swatch: None,
+        }
+    }
+
+    /// Modify how values are spaced along the x axis, consuming the parent.
+    #[inline]
+    pub fn with_x_mapping(mut self, mapping: Mapping) -> Self {
+        self.x.mapping = mapping;
+        self
+    }
+
+    /// Modify how values are spaced along the y axis, consuming the parent.
+    #[inline]
+    pub fn with_y_mapping(mut self, mapping: Mapping) -> Self {
+        self.y.mapping = mapping;
+        self
+    }
+
+    /// Modify about how many ticks to put on each axis, consuming the parent.
+    #[inline]
+    pub fn with_tick_count(mut self, tick_count: usize) -> Self {
+        self.tick_count = tick_count;
+        self
+    }
+
+    /// Modify the height of the capitals and the color of tick and legend [Text], consuming the parent.
+    ///
+    /// By default, text is black and a 25th of the height of the plot.
+    #[inline]
+    pub fn with_text(mut self, height: f32, color: Color) -> Self {
+        self.text_height = height;
+        self.text_color = color;
+        self
+    }
+
+    /// Get the scale of the x axis.
+    pub fn x_scale(&self) -> &AxisScale {
+        &self.x
+    }
+
+    /// Get the scale of the y axis.
+    pub fn y_scale(&self) -> &AxisScale {
+        &self.y
+    }
+
+    /// Map a point in data to World Space.
+    pub fn to_world<P: Into<Vec2>>(&self, point: P) -> Vec2 {
+        let point = point.into();
+        Vec2::new(self.x.map(point.x), self.y.map(point.y))
+    }
+
+    /// Get the shapes drawn so far.
+    pub fn shapes(&self) -> &[Shape] {
+        &self.shapes
+    }
+
+    /// Take the shapes drawn so far.
+    pub fn into_shapes(self) -> Vec<Shape> {
+        self.shapes
+    }
+
+    /// Draw every shape of the plot onto a canvas, in World Space.
+    pub fn draw(self, canvas: &mut Canvas) {
+        for shape in self.shapes {
+            canvas.draw_shape_absolute(shape.points, shape.stroke, shape.fill);
+        }
+    }
+
+    /// Draw the x axis along the bottom of the plot and the y axis along its left, with a mark at each tick.
+    ///
+    /// Each tick is labelled with its value, below or left of its mark.
+    pub fn axes(&mut self, stroke: Stroke) {
+        let (min, max) = self.bounds();
+        let length = stroke.width * 4.0;
+
+        self.push(
+            vec![Vec2::new(min.x, max.y), min, Vec2::new(max.x, min.y)],
+            Some(stroke),
+            None,
+        );
+
+        for tick in self.x.ticks(self.tick_count) {
+            self.push(
+                vec![
+                    Vec2::new(tick.position, min.y),
+                    Vec2::new(tick.position, min.y - length),
+                ],
+                Some(stroke),
+                None,
+            );
+            self.text(
+                Text::new(
+                    tick.label,
+                    (tick.position, min.y - length * 2.0),
+                    self.text_height,
+                )
+                .with_anchor((0.5, 1.0)),
+            );
+        }
+
+        for tick in self.y.ticks(self.tick_count) {
+            self.push(
+                vec![
+                    Vec2::new(min.x, tick.position),
+                    Vec2::new(min.x - length, tick.position),
+                ],
+                Some(stroke),
+                None,
+            );
+            self.text(
+                Text::new(
+                    tick.label,
+                    (min.x - length * 2.0, tick.position),
+                    self.text_height,
+                )
+                .with_anchor((1.0, 0.5)),
+            );
+        }
+    }
+
+    /// Draw a line across the plot at each tick of both axes.
+    pub fn grid(&mut self, stroke: Stroke) {
+        let (min, max) = self.bounds();
+
+        for tick in self.x.ticks(self.tick_count) {
+            self.push(
+                vec![
+                    Vec2::new(tick.position, min.y),
+                    Vec2::new(tick.position, max.y),
+                ],
+                Some(stroke),
+                None,
+            );
+        }
+
+        for tick in self.y.ticks(self.tick_count) {
+            self.push(
+                vec![
+                    Vec2::new(min.x, tick.position),
+                    Vec2::new(max.x, tick.position),
+                ],
+                Some(stroke),
+                None,
+            );
+        }
+    }
+
+    /// Draw a line through points in data.
+    ///
+    /// Points with a value that isn't finite leave a gap in the line.
+    pub fn line<P: Into<Vec2>, I: IntoIterator<Item = P>>(&mut self, data: I, stroke: Stroke) {
+        let mut points = Vec::new();
+
+        for point in data.into_iter().map(Into::into) {
+            if point.is_finite() {
+                points.push(self.to_world(point));
+            } else {
+                self.push(std::mem::take(&mut points), Some(stroke), None);
+            }
+        }
+        self.push(points, Some(stroke), None);
+
+        self.last_swatch = Some(Swatch::Line(stroke));
+    }
+
+    /// Draw a [Marker] at each point in data, facing right and measured in units of `size`.
+    pub fn scatter<P: Into<Vec2>, I: IntoIterator<Item = P>>(
+        &mut self,
+        data: I,
+        marker: Marker,
+        size: f32,
+        color: Color,
+    ) {
+        for point in data.into_iter().map(Into::into) {
+            if point.is_finite() {
+                let shape = marker_shape(&marker, self.to_world(point), size, color);
+                self.shapes.push(shape);
+            }
+        }
+
+        self.last_swatch = Some(Swatch::Marker(marker, size, color));
+    }
+
+    /// Draw a bar from the baseline up to each point in data, `width` wide in data along the x axis.
+    ///
+    /// The baseline is zero, or the end of the y axis closest to it.
+    pub fn bars<P: Into<Vec2>, I: IntoIterator<Item = P>>(
+        &mut self,
+        data: I,
+        width: f32,
+        fill: Color,
+    ) {
+        let baseline = self.baseline();
+
+        for point in data.into_iter().map(Into::into) {
+            if point.is_finite() {
+                let left = self.x.map(point.x - width / 2.0);
+                let right = self.x.map(point.x + width / 2.0);
+                let top = self.y.map(point.y);
+
+                self.push(
+                    vec![
+                        Vec2::new(left, baseline),
+                        Vec2::new(right, baseline),
+                        Vec2::new(right, top),
+                        Vec2::new(left, top),
+                        Vec2::new(left, baseline),
+                    ],
+                    None,
+                    Some(fill),
+                );
+            }
+        }
+
+        self.last_swatch = Some(Swatch::Fill(fill));
+    }
+
+    /// Fill the area between a line through points in data and the baseline.
+    ///
+    /// See [bars](Self::bars) for where the baseline is.
+    pub fn area<P: Into<Vec2>, I: IntoIterator<Item = P>>(&mut self, data: I, fill: Color) {
+        let baseline = self.baseline();
+
+        let mut points: Vec<Vec2> = data
+            .into_iter()
+            .map(Into::into)
+            .filter(|point: &Vec2| point.is_finite())
+            .map(|point| self.to_world(point))
+            .collect();
+
+        if let (Some(first), Some(last)) = (points.first().copied(), points.last().copied()) {
+            points.push(Vec2::new(last.x, baseline));
+            points.push(Vec2::new(first.x, baseline));
+            points.push(first);
+            self.push(points, None, Some(fill));
+        }
+
+        self.last_swatch = Some(Swatch::Fill(fill));
+    }
+
+    /// Add the most recently drawn series to the legend.
+    pub fn set_last_series_label<S: Into<String>>(&mut self, label: S) {
+        if let Some(swatch) = self.last_swatch.take() {
+            self.legend.push((label.into(), swatch));
+        }
+    }
+
+    /// Draw a swatch for each labelled series, in rows going down from `top_left`, each `row_height` tall.
+    ///
+    /// Each row is labelled to the right of its swatch.
+    pub fn legend<P: Into<Vec2>>(&mut self, top_left: P, row_height: f32) {
+        let top_left = top_left.into();
+        let swatch_width = row_height * 1.5;
+
+        for (index, (text, swatch)) in self.legend.clone().into_iter().enumerate() {
+            let top = top_left.y - row_height * index as f32;
+            let middle = top - row_height / 2.0;
+            let inset = row_height * 0.2;
+
+            match swatch {
+                Swatch::Line(stroke) => self.push(
+                    vec![
+                        Vec2::new(top_left.x, middle),
+                        Vec2::new(top_left.x + swatch_width, middle),
+                    ],
+                    Some(stroke),
+                    None,
+                ),
+                Swatch::Marker(marker, size, color) => self.shapes.push(marker_shape(
+                    &marker,
+                    Vec2::new(top_left.x + swatch_width / 2.0, middle),
+                    size,
+                    color,
+                )),
+                Swatch::Fill(fill) => {
+                    let (min, max) = (
+                        Vec2::new(top_left.x, top - row_height + inset),
+                        Vec2::new(top_left.x + swatch_width, top - inset),
+                    );
+                    self.push(
+                        vec![
+                            min,
+                            Vec2::new(max.x, min.y),
+                            max,
+                            Vec2::new(min.x, max.y),
+                            min,
+                        ],
+                        None,
+                        Some(fill),
+                    );
+                }
+            }
+
+            self.text(
+                Text::new(
+                    text,
+                    (top_left.x + swatch_width + row_height / 2.0, middle),
+                    self.text_height,
+                )
+                .with_anchor((0.0, 0.5)),
+            );
+        }
+    }
+
+    /// Get the corners of the plot's rectangle in World Space.
+    fn bounds(&self) -> (Vec2, Vec2) {
+        let (a, b) = (
+            Vec2::new(self.x.range.0, self.y.range.0),
+            Vec2::new(self.x.range.1, self.y.range.1),
+        );
+        (a.min(b), a.max(b))
+    }
+
+    /// Get where bars and areas start from, in World Space.
+    fn baseline(&self) -> f32 {
+        let (low, high) = self.y.domain;
+        let value = match self.y.mapping {
+            Mapping::Linear => 0.0f32.clamp(low.min(high), low.max(high)),
+            Mapping::Log => low.min(high),
+        };
+
+        self.y.map(value)
+    }
+
+    /// Draw text in the plot's text color, with strokes an eighth of its height wide.
+    fn text(&mut self, text: Text) {
+        let stroke = Stroke::new(self.text_color, text.height / 8.0, LineEnd::Round);
+        self.shapes.extend(text.shapes(stroke));
+    }
+
+    fn push(&mut self, points: Vec<Vec2>, stroke: Option<Stroke>, fill: Option<Color>) {
+        if points.len() > 1 {
+            self.shapes.push(Shape {
+                points,
+                stroke,
+                fill,
+                pattern: None,
+                id: None,
+                markers: None,
+            });
+        }
+    }
+}
+
+/// Build the shape of a marker at `center`, facing right.
+fn marker_shape(marker: &Marker, center: Vec2, size: f32, color: Color) -> Shape {
+    let points = marker
+        .outline()
+        .into_iter()
+        .map(|point| center + point * size)
+        .collect();
+
+    let (stroke, fill) = if marker.is_stroked() {
+        (Some(Stroke::new(color, size, LineEnd::Round)), None)
+    } else {
+        (None, Some(color))
+    };
+
+    Shape {
+        points,
+        stroke,
+        fill,
+        pattern: None,
+        id: None,
+        markers: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that scales map and invert values, and pick round ticks.
+    #[test]
+    fn scales_and_ticks() {
+        let linear = AxisScale::linear((-1.0, 1.0), (0.0, 10.0));
+        assert_eq!(linear.map(0.5), 7.5);
+        assert_eq!(linear.invert(2.5), -0.5);
+
+        let ticks = linear.ticks(4);
+        let labels: Vec<&str> = ticks.iter().map(|tick| tick.label.as_str()).collect();
+        assert_eq!(labels, ["-1.0", "-0.5", "0.0", "0.5", "1.0"]);
+
+        let ticks = AxisScale::linear((3.0, 97.0), (0.0, 1.0)).ticks(5);
+        let labels: Vec<&str> = ticks.iter().map(|tick| tick.label.as_str()).collect();
+        assert_eq!(labels, ["20", "40", "60", "80"]);
+
+        let log = AxisScale::log((1.0, 1000.0), (0.0, 3.0));
+        assert!((log.map(100.0) - 2.0).abs() < 0.0001);
+        assert!((log.invert(1.0) - 10.0).abs() < 0.001);
+
+        let ticks = log.ticks(4);
+        let labels: Vec<&str> = ticks.iter().map(|tick| tick.label.as_str()).collect();
+        assert_eq!(labels, ["1", "10", "100", "1000"]);
+
+        let ticks = AxisScale::log((0.5, 20.0), (0.0, 1.0)).ticks(5);
+        let labels: Vec<&str> = ticks.iter().map(|tick| tick.label.as_str()).collect();
+        assert_eq!(labels, ["0.5", "1.0", "2.0", "5.0", "10.0", "20.0"]);
+    }
+
+    /// Verify that a chart is made of plain shapes in World Space, including the text of its ticks and legend.
+    #[test]
+    fn builds_chart() {
+        let stroke = Stroke::new(Color::black(), 0.01, LineEnd::Butt);
+        let mut plot = Plot::new((-1.0, -1.0), (1.0, 1.0), (0.0, 4.0), (0.0, 10.0))
+            .with_tick_count(2)
+            .with_text(0.05, Color::black());
+        let strokes = |text: &str| Text::new(text, Vec2::ZERO, 1.0).strokes().len();
+
+        plot.axes(stroke);
+        let labels: usize = ["0", "2", "4", "0", "5", "10"]
+            .iter()
+            .map(|text| strokes(text))
+            .sum();
+        let axes = 1 + 6 + labels;
+        assert_eq!(plot.shapes().len(), axes);
+
+        // The label of the middle tick of the x axis hangs centered below its mark.
+        let two = Text::new("2", (0.0, -1.08), 0.05).with_anchor((0.5, 1.0));
+        let start = 1 + 1 + strokes("0") + 1;
+        let drawn: Vec<&[Vec2]> = plot.shapes()[start..start + strokes("2")]
+            .iter()
+            .map(|shape| shape.points.as_slice())
+            .collect();
+        assert_eq!(drawn, two.strokes());
+
+        plot.bars([(1.0, 5.0), (3.0, 10.0)], 1.0, Color::red());
+        plot.set_last_series_label("Bars");
+        plot.line(
+            [
+                (0.0, 0.0),
+                (1.0, 5.0),
+                (2.0, f32::NAN),
+                (3.0, 10.0),
+                (4.0, 0.0),
+            ],
+            stroke,
+        );
+        plot.set_last_series_label("Line");
+        plot.scatter([(2.0, 5.0)], Marker::Circle, 0.02, Color::blue());
+
+        plot.legend((0.5, 1.0), 0.1);
+        let line = Text::new("Line", (0.7, 0.85), 0.05).with_anchor((0.0, 0.5));
+
+        let shapes = plot.into_shapes();
+        // 2 bars, a line broken in two, a scatter point, and 2 swatches with their text.
+        assert_eq!(
+            shapes.len(),
+            axes + 2 + 2 + 1 + 2 + strokes("Bars") + strokes("Line")
+        );
+        assert_eq!(
+            shapes[axes].points,
+            vec![
+                Vec2::new(-0.75, -1.0),
+                Vec2::new(-0.25, -1.0),
+                Vec2::new(-0.25, 0.0),
+                Vec2::new(-0.75, 0.0),
+                Vec2::new(-0.75, -1.0),
+            ]
+        );
+        let last = line.strokes().pop().unwrap();
+        for (drawn, expected) in shapes.last().unwrap().points.iter().zip(&last) {
+            assert!(drawn.abs_diff_eq(*expected, 0.0001));
+        }
+    }
+}
@@ -0,0 +1,480 @@
+use std::{collections::HashMap, fmt::Write, time::Duration};
+
+use glam::Vec2;
+
+use super::svg_renderer::escape_xml;
+use super::SvgRenderer;
+use crate::canvas::ShapeCollector;
+use crate::{Canvas, Color, LineEnd, Page, Renderer, Shape};
+
+/// A renderer that animates between several [Canvas] keyframes using SVG's `<animate>` element.
+///
+/// Shapes are matched between keyframes by their [id](Shape::id).
+/// A shape that appears exactly once in every keyframe, with the same number of points, the same kind of stroke and fill, and no pattern,
+/// is drawn once and has its points, colors, and stroke width interpolated.
+/// Every other shape is drawn once per keyframe and swapped in and out using `visibility`,
+/// with the keyframe's index added to its id after the first keyframe, like `"line-frame2"`.
+///
+/// Shapes are layered in the order of the first keyframe.
+/// Swapped shapes from later keyframes are placed after the closest matched shape that precedes them.
+#[derive(Clone)]
+pub struct AnimatedSvgRenderer {
+    renderer: SvgRenderer,
+    keyframe_duration: Duration,
+    looping: bool,
+}
+
+/// A single element in the output document.
+enum Entry<'a> {
+    /// A shape matched across every keyframe.
+    Animated(&'a str),
+    /// A shape only shown during a single keyframe.
+    Swapped(usize, &'a Shape),
+}
+
+impl AnimatedSvgRenderer {
+    /// Creates a new [AnimatedSvgRenderer].
+    ///
+    /// `keyframe_duration` is the time between each keyframe.
+    /// If `looping` is `true`, the last keyframe animates back into the first and the animation repeats forever.
+    /// Otherwise, the animation holds on the last keyframe.
+    ///
+    /// `preserve_height` allows you to decide which axis to preserve.
+    /// If `true`, then the rendered image will map `-1..=1` in the y axis in camera space to `size.y..=0`.
+    /// If `false` then the rendered image will be mapped for the x axis.
+    pub fn new(
+        size: Vec2,
+        background: Option<Color>,
+        preserve_height: bool,
+        keyframe_duration: Duration,
+        looping: bool,
+    ) -> Self {
+        Self {
+            renderer: SvgRenderer::new(size, background, false, preserve_height, usize::MAX),
+            keyframe_duration,
+            looping,
+        }
+    }
+
+    /// Creates a new [AnimatedSvgRenderer] that fills a [Page].
+    ///
+    /// See [SvgRenderer::from_page].
+    pub fn from_page(
+        page: Page,
+        background: Option<Color>,
+        preserve_height: bool,
+        keyframe_duration: Duration,
+        looping: bool,
+    ) -> Self {
+        Self {
+            renderer: SvgRenderer::from_page(page, background, false, preserve_height, usize::MAX),
+            keyframe_duration,
+            looping,
+        }
+    }
+
+    /// Render the keyframes, in order, into a single animated document.
+    pub fn render<'a, I>(mut self, keyframes: I) -> String
+    where
+        I: IntoIterator<Item = &'a Canvas>,
+    {
+        let frames: Vec<Vec<Shape>> = keyframes
+            .into_iter()
+            .map(|canvas| canvas.render(ShapeCollector::default()))
+            .collect();
+
+        if frames.is_empty() {
+            return self.renderer.finalize();
+        }
+
+        let tracks = Self::find_tracks(&frames);
+
+        // Lay out the first keyframe, then attach the rest after their closest preceding matched shape.
+        let mut base = Vec::new();
+        for shape in &frames[0] {
+            match shape.id.as_deref() {
+                Some(id) if tracks.contains_key(id) => base.push(Entry::Animated(id)),
+                _ => base.push(Entry::Swapped(0, shape)),
+            }
+        }
+
+        let base_indices: HashMap<&str, usize> = base
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| match entry {
+                Entry::Animated(id) => Some((*id, i + 1)),
+                Entry::Swapped(..) => None,
+            })
+            .collect();
+
+        let mut attachments: Vec<Vec<Entry>> = (0..=base.len()).map(|_| Vec::new()).collect();
+        for (frame_index, frame) in frames.iter().enumerate().skip(1) {
+            let mut anchor = 0;
+
+            for shape in frame {
+                match shape.id.as_deref().and_then(|id| base_indices.get(id)) {
+                    Some(index) => anchor = *index,
+                    None => attachments[anchor].push(Entry::Swapped(frame_index, shape)),
+                }
+            }
+        }
+
+        let mut entries = Vec::new();
+        let mut attachments = attachments.into_iter();
+        entries.append(&mut attachments.next().unwrap());
+        for (entry, mut attached) in base.into_iter().zip(attachments) {
+            entries.push(entry);
+            entries.append(&mut attached);
+        }
+
+        let mut open_group = None;
+        for entry in entries {
+            match entry {
+                Entry::Animated(id) => {
+                    self.close_group(&mut open_group);
+                    self.write_animated(id, &tracks[id], frames.len());
+                }
+                Entry::Swapped(frame_index, shape) => {
+                    if open_group != Some(frame_index) {
+                        self.close_group(&mut open_group);
+                        self.open_group(frame_index, frames.len());
+                        open_group = Some(frame_index);
+                    }
+
+                    // The same id can be swapped in by several keyframes, so later ones are numbered to keep ids unique.
+                    match &shape.id {
+                        Some(id) if frame_index > 0 => {
+                            let mut shape = shape.clone();
+                            shape.id = Some(format!("{}-frame{}", id, frame_index));
+                            self.renderer.render(&shape);
+                        }
+                        _ => self.renderer.render(shape),
+                    }
+                }
+            }
+        }
+        self.close_group(&mut open_group);
+
+        self.renderer.finalize()
+    }
+
+    /// Find every id whose shapes correspond across all keyframes.
+    fn find_tracks(frames: &[Vec<Shape>]) -> HashMap<&str, Vec<&Shape>> {
+        let mut occurrences: HashMap<&str, Vec<(usize, &Shape)>> = HashMap::new();
+
+        for (frame_index, frame) in frames.iter().enumerate() {
+            for shape in frame {
+                if let Some(id) = &shape.id {
+                    occurrences
+                        .entry(id)
+                        .or_default()
+                        .push((frame_index, shape));
+                }
+            }
+        }
+
+        occurrences
+            .into_iter()
+            .filter(|(_, occurrences)| {
+                // Each id must appear exactly once in every keyframe.
+                occurrences.len() == frames.len()
+                    && occurrences
+                        .iter()
+                        .enumerate()
+                        .all(|(i, (frame_index, _))| i == *frame_index)
+                    && occurrences
+                        .iter()
+                        .all(|(_, shape)| Self::corresponds(occurrences[0].1, shape))
+            })
+            .map(|(id, occurrences)| {
+                (
+                    id,
+                    occurrences.into_iter().map(|(_, shape)| shape).collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Checks if the attributes of two shapes can be interpolated between.
+    fn corresponds(a: &Shape, b: &Shape) -> bool {
+        a.is_drawable()
+            && a.points.len() == b.points.len()
+            && a.is_polygon() == b.is_polygon()
+            && a.stroke.map(|stroke| stroke.line_end) == b.stroke.map(|stroke| stroke.line_end)
+            && a.fill.is_some() == b.fill.is_some()
+            && a.pattern.is_none()
+            && b.pattern.is_none()
+    }
+
+    /// Write a shape that is matched across all keyframes, along with its animations.
+    fn write_animated(&mut self, id: &str, track: &[&Shape], frame_count: usize) {
+        let first = track[0];
+        let image_transform = self.renderer.image_transform();
+        let renderer = &self.renderer;
+
+        let mut attributes: Vec<(&str, Vec<String>)> = vec![(
+            "points",
+            track
+                .iter()
+                .map(|shape| {
+                    let mut points = String::new();
+                    for point in &shape.points {
+                        let point = image_transform.transform_point2(*point);
+                        write!(
+                            points,
+                            "{},{} ",
+                            renderer.number(point.x),
+                            renderer.number(point.y)
+                        )
+                        .unwrap();
+                    }
+                    points
+                })
+                .collect(),
+        )];
+
+        if first.stroke.is_some() {
+            attributes.push((
+                "stroke",
+                track
+                    .iter()
+                    .map(|shape| shape.stroke.unwrap().color.as_hex(false))
+                    .collect(),
+            ));
+            attributes.push((
+                "stroke-opacity",
+                track
+                    .iter()
+                    .map(|shape| shape.stroke.unwrap().color.a().to_string())
+                    .collect(),
+            ));
+            attributes.push((
+                "stroke-width",
+                track
+                    .iter()
+                    .map(|shape| {
+                        renderer
+                            .number(shape.stroke.unwrap().width * image_transform.matrix2.x_axis.x)
+                    })
+                    .collect(),
+            ));
+        }
+
+        if first.fill.is_some() {
+            attributes.push((
+                "fill",
+                track
+                    .iter()
+                    .map(|shape| shape.fill.unwrap().as_hex(false))
+                    .collect(),
+            ));
+            attributes.push((
+                "fill-opacity",
+                track
+                    .iter()
+                    .map(|shape| shape.fill.unwrap().a().to_string())
+                    .collect(),
+            ));
+        }
+
+        let repeat_attribute = self.repeat_attribute();
+        let looping = self.looping;
+        let dur = self.keyframe_duration.as_secs_f32() * frame_count as f32;
+        self.renderer.begin_element();
+        let document = self.renderer.document_mut();
+
+        write!(
+            document,
+            "<{} id=\"{}\"",
+            if first.is_polygon() {
+                "polygon"
+            } else {
+                "polyline"
+            },
+            escape_xml(id)
+        )
+        .unwrap();
+
+        for (name, values) in &attributes {
+            write!(document, " {}=\"{}\"", name, values[0]).unwrap();
+        }
+
+        if first.fill.is_none() {
+            write!(document, " fill=\"none\"").unwrap();
+        }
+
+        match first.stroke.map(|stroke| stroke.line_end) {
+            Some(LineEnd::Butt) => write!(document, " stroke-linecap=\"butt\"").unwrap(),
+            Some(LineEnd::Round) => write!(document, " stroke-linecap=\"round\"").unwrap(),
+            None => (),
+        }
+
+        write!(document, ">").unwrap();
+
+        let key_times = (0..=frame_count)
+            .map(|i| (i as f32 / frame_count as f32).to_string())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        for (name, mut values) in attributes {
+            if values.iter().all(|value| *value == values[0]) {
+                continue;
+            }
+
+            let last = if looping {
+                values[0].clone()
+            } else {
+                values[values.len() - 1].clone()
+            };
+            values.push(last);
+
+            write!(
+                document,
+                "<animate attributeName=\"{}\" values=\"{}\" keyTimes=\"{}\" dur=\"{}s\" {}/>",
+                name,
+                values.join(";"),
+                key_times,
+                dur,
+                repeat_attribute
+            )
+            .unwrap();
+        }
+
+        write!(
+            document,
+            "</{}>",
+            if first.is_polygon() {
+                "polygon"
+            } else {
+                "polyline"
+            }
+        )
+        .unwrap();
+    }
+
+    /// Open a group that is only visible during a single keyframe.
+    fn open_group(&mut self, frame_index: usize, frame_count: usize) {
+        let mut keys = vec![(0.0, frame_index == 0)];
+        if frame_index > 0 {
+            keys.push((frame_index as f32 / frame_count as f32, true));
+        }
+        if frame_index + 1 < frame_count || self.looping {
+            keys.push(((frame_index + 1) as f32 / frame_count as f32, false));
+        }
+
+        let visibility = |visible: bool| if visible { "visible" } else { "hidden" };
+
+        let repeat_attribute = self.repeat_attribute();
+        let dur = self.keyframe_duration.as_secs_f32() * frame_count as f32;
+
+        self.renderer.begin_element();
+        write!(
+            self.renderer.document_mut(),
+            "<g visibility=\"{}\"><animate attributeName=\"visibility\" calcMode=\"discrete\" values=\"{}\" keyTimes=\"{}\" dur=\"{}s\" {}/>",
+            visibility(frame_index == 0),
+            keys.iter()
+                .map(|(_, visible)| visibility(*visible))
+                .collect::<Vec<_>>()
+                .join(";"),
+            keys.iter()
+                .map(|(time, _)| time.to_string())
+                .collect::<Vec<_>>()
+                .join(";"),
+            dur,
+            repeat_attribute
+        )
+        .unwrap();
+    }
+
+    /// Close the currently open group, if any.
+    fn close_group(&mut self, open_group: &mut Option<usize>) {
+        if open_group.take().is_some() {
+            self.renderer.begin_element();
+            write!(self.renderer.document_mut(), "</g>").unwrap();
+        }
+    }
+
+    fn repeat_attribute(&self) -> &'static str {
+        if self.looping {
+            "repeatCount=\"indefinite\""
+        } else {
+            "fill=\"freeze\""
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Marker, Markers, Stroke};
+
+    /// Verify that matched shapes are interpolated and unmatched shapes are swapped.
+    #[test]
+    fn matches_shapes_by_id() {
+        let keyframes: Vec<Canvas> = [0.0, 0.5]
+            .iter()
+            .map(|x| {
+                let mut canvas = Canvas::default();
+                canvas.draw_line(
+                    (*x, 0.0),
+                    (*x, 1.0),
+                    Some(Stroke::new(Color::black(), 0.1, LineEnd::Butt)),
+                    None,
+                );
+                canvas.set_last_shape_id("line");
+                canvas.draw_triangle((0.0, 0.0), (*x, 1.0), (1.0, 0.0), None, Some(Color::red()));
+                canvas
+            })
+            .collect();
+
+        let svg =
+            AnimatedSvgRenderer::new(Vec2::splat(100.0), None, true, Duration::from_secs(1), true)
+                .render(&keyframes);
+
+        assert_eq!(svg.matches("<polyline id=\"line\"").count(), 1);
+        assert!(svg.contains(
+            "<animate attributeName=\"points\" values=\"50,50 50,0 ;75,50 75,0 ;50,50 50,0 \""
+        ));
+        assert!(!svg.contains("attributeName=\"stroke\""));
+        assert_eq!(svg.matches("attributeName=\"visibility\"").count(), 2);
+    }
+
+    /// Verify that shapes and markers that can't be matched across keyframes keep their ids unique in every keyframe.
+    #[test]
+    fn swapped_ids_are_unique() {
+        let keyframes: Vec<Canvas> = [2, 3, 4]
+            .iter()
+            .map(|count| {
+                let mut canvas = Canvas::default();
+                let points: Vec<Vec2> = (0..*count)
+                    .map(|i| Vec2::new(i as f32 / 4.0, 0.0))
+                    .collect();
+                canvas.draw_shape(
+                    points,
+                    Some(Stroke::new(Color::black(), 0.1, LineEnd::Butt)),
+                    None,
+                );
+                canvas.set_last_shape_id("line");
+                // Markers with different outlines can't be matched either.
+                let marker = if *count == 3 {
+                    Marker::Bar
+                } else {
+                    Marker::Arrow
+                };
+                canvas.set_last_shape_markers(Markers::end(marker));
+                canvas
+            })
+            .collect();
+
+        let svg =
+            AnimatedSvgRenderer::new(Vec2::splat(100.0), None, true, Duration::from_secs(1), true)
+                .render(&keyframes);
+
+        assert_eq!(svg.matches("id=\"line\"").count(), 1);
+        assert_eq!(svg.matches("id=\"line-marker-end\"").count(), 1);
+        assert_eq!(svg.matches("id=\"line-marker-end-frame2\"").count(), 1);
+        assert_eq!(svg.matches("id=\"line-frame1\"").count(), 1);
+        assert_eq!(svg.matches("id=\"line-frame2\"").count(), 1);
+        assert_eq!(svg.matches("attributeName=\"visibility\"").count(), 3);
+    }
+}
@@ -1,10 +1,22 @@
+#[cfg(feature = "svg_renderer")]
+mod animated_svg_renderer;
 #[cfg(feature = "tiny_skia_renderer")]
 mod skia_renderer;
 #[cfg(feature = "svg_renderer")]
+mod streaming_svg_renderer;
+#[cfg(feature = "svg_renderer")]
 mod svg_renderer;
+#[cfg(feature = "tiny_skia_renderer")]
+mod tiled_skia_renderer;
 
+#[cfg(feature = "svg_renderer")]
+pub use animated_svg_renderer::AnimatedSvgRenderer;
+#[cfg(feature = "svg_renderer")]
+pub use streaming_svg_renderer::StreamingSvgRenderer;
 #[cfg(feature = "svg_renderer")]
 pub use svg_renderer::SvgRenderer;
 
 #[cfg(feature = "tiny_skia_renderer")]
-pub use skia_renderer::SkiaRenderer;
+pub use skia_renderer::{Rgba16SkiaRenderer, Rgba32FSkiaRenderer, SkiaRenderer};
+#[cfg(feature = "tiny_skia_renderer")]
+pub use tiled_skia_renderer::TiledSkiaRenderer;
@@ -1,18 +1,88 @@
-use glam::{UVec2, Vec2};
-use image::RgbaImage;
-use tiny_skia::{FillRule, LineCap, Paint, PathBuilder, Pixmap, Transform};
+use glam::{Affine2, Mat2, UVec2, Vec2, Vec3, Vec4};
+use std::io::Write;
+
+use image::{
+    codecs::hdr::HdrEncoder, ImageBuffer, ImageResult, Rgb, Rgba, Rgba32FImage, RgbaImage,
+};
+use tiny_skia::{
+    ClipMask, ColorU8, FillRule, FilterQuality, LineCap, Paint, Path, PathBuilder, Pixmap, Rect,
+    Shader, SpreadMode, Transform,
+};
 
 use crate::canvas::Shape;
-use crate::{Color, LineEnd, Renderer};
+use crate::color::{linear_to_srgb, srgb_to_linear};
+use crate::{
+    Color, LineEnd, Page, Pattern, PatternTile, RenderError, Renderer, RepeatMode, TryRenderer,
+    Viewport,
+};
 
 /// Renderer that uses the [tiny_skia](https://github.com/RazrFalcon/tiny-skia) crate.
 /// This is NOT actual Skia, but a Rust port.
+///
+/// By default, shapes are blended on the sRGB encoded values, like most image editors and browsers do.
+/// Use [with_linear_blending](Self::with_linear_blending) to blend in linear light instead.
 #[derive(Clone)]
 pub struct SkiaRenderer {
     antialias: bool,
     scale: f32,
     center_offset: Vec2,
+    /// Where the top left corner of `canvas` is in the full image, when only rendering part of it.
+    ///
+    /// Paths are built in the full image and moved by this whole number of pixels when they are rasterized,
+    /// so they come out exactly like they would in the full image wherever they are not cut off by `canvas`.
+    origin: Vec2,
     canvas: Pixmap,
+    background: Option<Color>,
+    /// Whether shapes are blended in linear light, rather than on the sRGB encoded values.
+    linear: bool,
+    /// Premultiplied floating point pixels, used instead of `canvas` when blending in linear light or for deep output.
+    ///
+    /// They are in linear light if `linear` is set, and sRGB encoded otherwise.
+    float: Option<Vec<Vec4>>,
+    clip: Option<Clip>,
+    /// The clip that was active when each open group began.
+    clip_stack: Vec<Option<Clip>>,
+}
+
+/// A clip, along with the polygons it was built from, so it can be rebuilt for just part of the canvas.
+#[derive(Clone)]
+struct Clip {
+    /// The clip for the whole of `canvas`.
+    mask: ClipMask,
+    /// The polygons that the clip is the intersection of, in the full image.
+    paths: Vec<Path>,
+    /// The box around everything the clip lets through, in the full image, as `(min, max)`.
+    bounds: (Vec2, Vec2),
+}
+
+impl Clip {
+    /// Build the clip for a pixmap that is `width` by `height` pixels, with paths moved into it by `transform`.
+    ///
+    /// Returns `None` if the clip hides the whole pixmap.
+    fn mask(
+        &self,
+        width: u32,
+        height: u32,
+        transform: Transform,
+        antialias: bool,
+    ) -> Option<ClipMask> {
+        let mut mask = ClipMask::new();
+        for path in &self.paths {
+            let path = path.clone().transform(transform)?;
+            if mask.is_empty() {
+                mask.set_path(width, height, &path, FillRule::Winding, antialias)?;
+            } else {
+                mask.intersect_path(&path, FillRule::Winding, antialias)?;
+            }
+        }
+        Some(mask)
+    }
+}
+
+/// What to paint a path with.
+enum Source<'a> {
+    Solid(Color),
+    Shader(Shader<'a>),
 }
 
 impl SkiaRenderer {
@@ -21,48 +91,418 @@ impl SkiaRenderer {
     /// `preserve_height` allows you to decide which axis to preserve.
     /// If `true`, then the rendered image will map `-1..=1` in the y axis in camera space to `size.y..=0`.
     /// If `false` then the rendered image will be mapped for the x axis.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either dimension of `size` is zero. Use [try_new](Self::try_new) to handle that instead.
     pub fn new(
         size: UVec2,
         background: Option<Color>,
         antialias: bool,
         preserve_height: bool,
     ) -> Self {
-        let mut canvas = Pixmap::new(size.x, size.y).unwrap();
+        match Self::try_new(size, background, antialias, preserve_height) {
+            Ok(renderer) => renderer,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Create a new [SkiaRenderer], or a [RenderError::InvalidSize] if either dimension of `size` is zero.
+    ///
+    /// See [new](Self::new).
+    pub fn try_new(
+        size: UVec2,
+        background: Option<Color>,
+        antialias: bool,
+        preserve_height: bool,
+    ) -> Result<Self, RenderError> {
+        Self::try_new_region(
+            size,
+            UVec2::ZERO,
+            size,
+            background,
+            antialias,
+            preserve_height,
+        )
+    }
+
+    /// Create a new [SkiaRenderer] that only renders the part of an image that is `size` large
+    /// which is `region` large with its top left corner at `origin`, leaving out everything else.
+    pub(crate) fn try_new_region(
+        size: UVec2,
+        origin: UVec2,
+        region: UVec2,
+        background: Option<Color>,
+        antialias: bool,
+        preserve_height: bool,
+    ) -> Result<Self, RenderError> {
+        if size.min_element() == 0 {
+            return Err(RenderError::InvalidSize(size));
+        }
+
+        let mut canvas = Pixmap::new(region.x, region.y).ok_or(RenderError::InvalidSize(region))?;
 
         if let Some(background) = background {
             canvas.fill(background.into());
         }
 
-        let (scale, center_offset) = if preserve_height {
-            let scale = size.y as f32 / 2.0;
-            (scale, Vec2::new(size.x as f32 / 2.0 / scale, 1.0))
-        } else {
-            let scale = size.x as f32 / 2.0;
-            (scale, Vec2::new(1.0, size.y as f32 / 2.0 / scale))
-        };
+        let (scale, center_offset) = viewport(size, preserve_height);
 
-        Self {
+        Ok(Self {
             antialias,
             scale,
             center_offset,
+            origin: origin.as_vec2(),
             canvas,
+            background,
+            linear: false,
+            float: None,
+            clip: None,
+            clip_stack: Vec::new(),
+        })
+    }
+
+    /// Create a new [SkiaRenderer] that fills a [Page], rasterized at the page's DPI.
+    pub fn from_page(
+        page: Page,
+        background: Option<Color>,
+        antialias: bool,
+        preserve_height: bool,
+    ) -> Self {
+        Self::new(page.pixel_size(), background, antialias, preserve_height)
+    }
+
+    /// Composite shapes in linear light, converting back to sRGB at [finalize](Renderer::finalize).
+    ///
+    /// Blending in linear light makes antialiased edges and translucent overlaps physically accurate,
+    /// so they don't come out darker than they should. It is slower and uses more memory.
+    #[inline]
+    pub fn with_linear_blending(mut self, linear_blending: bool) -> Self {
+        self.linear = linear_blending;
+        self.float = None;
+        if linear_blending {
+            self.with_float_pixels()
+        } else {
+            self
+        }
+    }
+
+    /// Composite shapes in floating point, in the configured blending mode, instead of on 8 bit pixels.
+    fn with_float_pixels(mut self) -> Self {
+        let background = match self.background {
+            Some(background) => self.premultiplied(background),
+            None => Vec4::ZERO,
+        };
+
+        self.float = Some(vec![
+            background;
+            self.canvas.width() as usize
+                * self.canvas.height() as usize
+        ]);
+        self
+    }
+
+    /// Convert a color to the premultiplied form used by the floating point pixels.
+    fn premultiplied(&self, color: Color) -> Vec4 {
+        let a = color.a().clamp(0.0, 1.0);
+        let rgb = if self.linear {
+            let (r, g, b, _) = color.to_linear_srgb();
+            Vec3::new(r, g, b)
+        } else {
+            Vec3::new(color.r(), color.g(), color.b())
+        };
+
+        (rgb * a).extend(a)
+    }
+
+    /// Take the composited floating point pixels with straight alpha, in linear light if `linear` is set, or sRGB encoded.
+    ///
+    /// Must only be called when compositing in floating point.
+    fn into_float_pixels(self, linear: bool) -> impl Iterator<Item = Vec4> {
+        let convert: fn(f32) -> f32 = match (self.linear, linear) {
+            (true, false) => linear_to_srgb,
+            (false, true) => srgb_to_linear,
+            _ => |channel| channel,
+        };
+
+        self.float
+            .expect("Floating point compositing is enabled")
+            .into_iter()
+            .map(move |color| {
+                if color.w > 0.0 {
+                    let rgb = color.truncate() / color.w;
+                    Vec4::new(convert(rgb.x), convert(rgb.y), convert(rgb.z), color.w)
+                } else {
+                    Vec4::ZERO
+                }
+            })
+    }
+
+    /// Get the transform from Camera Space to Image Space.
+    fn image_transform(&self) -> Affine2 {
+        Affine2::from_mat2_translation(
+            Mat2::from_diagonal(Vec2::new(self.scale, -self.scale)),
+            self.center_offset * self.scale,
+        )
+    }
+
+    /// Get the transform from Image Space to the pixels of `canvas`.
+    fn canvas_transform(&self) -> Transform {
+        Transform::from_translate(-self.origin.x, -self.origin.y)
+    }
+
+    /// Rasterize a single tile of a [Pattern] and find the transform from its pixels to Image Space.
+    fn pattern_pixmap(&self, pattern: &Pattern) -> Option<(Pixmap, Transform)> {
+        let pixmap = match &pattern.tile {
+            PatternTile::Image(image) => {
+                let mut pixmap = Pixmap::new(image.width(), image.height())?;
+
+                for (pixel, rgba) in pixmap.pixels_mut().iter_mut().zip(image.pixels()) {
+                    *pixel = ColorU8::from_rgba(rgba[0], rgba[1], rgba[2], rgba[3]).premultiply();
+                }
+
+                pixmap
+            }
+            PatternTile::Canvas(canvas) => {
+                // Rasterize the tile at roughly the resolution it will be displayed at.
+                let to_image = self.image_transform() * pattern.transform;
+                let resolution = Vec2::new(
+                    to_image.matrix2.x_axis.length() * pattern.size.x,
+                    to_image.matrix2.y_axis.length() * pattern.size.y,
+                )
+                .round()
+                .clamp(Vec2::ONE, Vec2::splat(4096.0));
+
+                canvas.render(PixmapRenderer(SkiaRenderer::new(
+                    resolution.as_uvec2(),
+                    None,
+                    self.antialias,
+                    true,
+                )))?
+            }
+        };
+
+        let resolution = Vec2::new(pixmap.width() as f32, pixmap.height() as f32);
+        let transform =
+            to_skia_transform(self.image_transform() * pattern.tile_pixel_transform(resolution));
+
+        Some((pixmap, transform))
+    }
+
+    /// Paint a path, either filling it or stroking it.
+    fn draw(&mut self, path: &Path, source: Source, stroke: Option<&tiny_skia::Stroke>) {
+        let premultiplied = match source {
+            Source::Solid(color) => Some(self.premultiplied(color)),
+            Source::Shader(_) => None,
+        };
+        let linear = self.linear;
+        let transform = self.canvas_transform();
+
+        let float = match &mut self.float {
+            Some(float) => float,
+            None => {
+                let mut paint = Paint {
+                    anti_alias: self.antialias,
+                    ..Default::default()
+                };
+                match source {
+                    Source::Solid(color) => paint.set_color(color.into()),
+                    Source::Shader(shader) => paint.shader = shader,
+                }
+
+                Self::paint_path(
+                    &mut self.canvas,
+                    path,
+                    &paint,
+                    stroke,
+                    transform,
+                    self.clip.as_ref().map(|clip| &clip.mask),
+                );
+                return;
+            }
+        };
+
+        // Rasterize the coverage (or shaded pixels) of the path into a scratch buffer around the part of it the clip lets through.
+        // Miter joins reach out to half the miter limit times the stroke width, and antialiasing reaches a little further.
+        let margin = stroke.map_or(0.0, |stroke| {
+            stroke.width * stroke.miter_limit.max(1.0) / 2.0
+        }) + 2.0;
+        let bounds = path.bounds();
+        let mut min = Vec2::new(bounds.left(), bounds.top()) - margin;
+        let mut max = Vec2::new(bounds.right(), bounds.bottom()) + margin;
+        if let Some(clip) = &self.clip {
+            min = min.max(clip.bounds.0 - 2.0);
+            max = max.min(clip.bounds.1 + 2.0);
+        }
+
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        let size = Vec2::new(width as f32, height as f32);
+        let min = (min - self.origin).floor().clamp(Vec2::ZERO, size);
+        let max = (max - self.origin).ceil().clamp(Vec2::ZERO, size);
+        let (left, top, right, bottom) = (min.x as u32, min.y as u32, max.x as u32, max.y as u32);
+
+        let mut scratch = match Pixmap::new(right.saturating_sub(left), bottom.saturating_sub(top))
+        {
+            Some(scratch) => scratch,
+            None => return,
+        };
+
+        let transform = Transform::from_translate(
+            -(left as f32) - self.origin.x,
+            -(top as f32) - self.origin.y,
+        );
+        let clip = match &self.clip {
+            Some(clip) => {
+                match clip.mask(scratch.width(), scratch.height(), transform, self.antialias) {
+                    Some(mask) => Some(mask),
+                    None => return,
+                }
+            }
+            None => None,
+        };
+
+        let mut paint = Paint {
+            anti_alias: self.antialias,
+            ..Default::default()
+        };
+        match source {
+            Source::Solid(_) => paint.set_color(tiny_skia::Color::WHITE),
+            Source::Shader(shader) => paint.shader = shader,
+        }
+
+        Self::paint_path(&mut scratch, path, &paint, stroke, transform, clip.as_ref());
+
+        // Composite the scratch buffer with source-over, in the blending mode's space.
+        for (y, row) in scratch
+            .pixels()
+            .chunks(scratch.width() as usize)
+            .enumerate()
+        {
+            let start = (top as usize + y) * width as usize + left as usize;
+
+            for (pixel, destination) in row.iter().zip(&mut float[start..start + row.len()]) {
+                if pixel.alpha() == 0 {
+                    continue;
+                }
+
+                let source = match premultiplied {
+                    Some(color) => color * (pixel.alpha() as f32 / 255.0),
+                    None => {
+                        let pixel = pixel.demultiply();
+                        let a = pixel.alpha() as f32 / 255.0;
+                        let rgb = Vec3::new(
+                            pixel.red() as f32,
+                            pixel.green() as f32,
+                            pixel.blue() as f32,
+                        ) / 255.0;
+                        let rgb = if linear {
+                            Vec3::new(
+                                srgb_to_linear(rgb.x),
+                                srgb_to_linear(rgb.y),
+                                srgb_to_linear(rgb.z),
+                            )
+                        } else {
+                            rgb
+                        };
+                        (rgb * a).extend(a)
+                    }
+                };
+
+                *destination = source + *destination * (1.0 - source.w);
+            }
         }
     }
+
+    fn paint_path(
+        pixmap: &mut Pixmap,
+        path: &Path,
+        paint: &Paint,
+        stroke: Option<&tiny_skia::Stroke>,
+        transform: Transform,
+        clip: Option<&ClipMask>,
+    ) {
+        match stroke {
+            Some(stroke) => pixmap.stroke_path(path, paint, stroke, transform, clip),
+            None => pixmap.fill_path(path, paint, FillRule::Winding, transform, clip),
+        };
+    }
+
+    /// Transform from Camera Space (range from (-1, -1) to (1, 1)) to Image Space (range from (0, 0) to image size).
+    fn to_image_space(&self, point: Vec2) -> Vec2 {
+        (Vec2::new(point.x, -point.y) + self.center_offset) * self.scale
+    }
+}
+
+/// Wraps a [SkiaRenderer] to output its premultiplied [Pixmap] directly.
+struct PixmapRenderer(SkiaRenderer);
+
+impl Renderer for PixmapRenderer {
+    type Output = Option<Pixmap>;
+
+    fn render(&mut self, shape: &Shape) {
+        self.0.render(shape)
+    }
+
+    fn begin_group(&mut self, id: Option<&str>) {
+        self.0.begin_group(id)
+    }
+
+    fn end_group(&mut self) {
+        self.0.end_group()
+    }
+
+    fn set_clip(&mut self, clip: Option<&[Vec2]>) {
+        self.0.set_clip(clip)
+    }
+
+    fn viewport(&self) -> Option<Viewport> {
+        self.0.viewport()
+    }
+
+    fn finalize(self) -> Self::Output {
+        Some(self.0.canvas)
+    }
+}
+
+/// Find the scale and center offset that map Camera Space onto an image that is `size` large.
+pub(crate) fn viewport(size: UVec2, preserve_height: bool) -> (f32, Vec2) {
+    if preserve_height {
+        let scale = size.y as f32 / 2.0;
+        (scale, Vec2::new(size.x as f32 / 2.0 / scale, 1.0))
+    } else {
+        let scale = size.x as f32 / 2.0;
+        (scale, Vec2::new(1.0, size.y as f32 / 2.0 / scale))
+    }
+}
+
+fn to_skia_transform(transform: Affine2) -> Transform {
+    Transform::from_row(
+        transform.matrix2.x_axis.x,
+        transform.matrix2.x_axis.y,
+        transform.matrix2.y_axis.x,
+        transform.matrix2.y_axis.y,
+        transform.translation.x,
+        transform.translation.y,
+    )
 }
 
 impl Renderer for SkiaRenderer {
     type Output = RgbaImage;
 
+    fn viewport(&self) -> Option<Viewport> {
+        Some(Viewport {
+            min: -self.center_offset,
+            max: self.center_offset,
+            resolution: Some(self.scale),
+        })
+    }
+
     fn render(&mut self, shape: &Shape) {
-        if !shape.is_drawable(){
+        if !shape.is_drawable() {
             return;
         }
 
-        // Transform from Camera Space (range from (-1, -1) to (1, 1)) to Image Space (range from (0, 0) to image size).
-        let mut points = shape.points.iter().map(|p| {
-            let p = Vec2::new(p.x, -p.y) + self.center_offset;
-            p * self.scale
-        });
+        let mut points = shape.points.iter().map(|p| self.to_image_space(*p));
 
         if let Some(first) = points.next() {
             let mut path = PathBuilder::new();
@@ -88,48 +528,516 @@ impl Renderer for SkiaRenderer {
             let path = path.finish().unwrap();
 
             if let Some(stroke) = shape.stroke {
-                let mut paint = Paint::default();
-                paint.set_color(stroke.color.into());
-                paint.anti_alias = self.antialias;
-
-                self.canvas.stroke_path(
+                self.draw(
                     &path,
-                    &paint,
-                    &tiny_skia::Stroke {
+                    Source::Solid(stroke.color),
+                    Some(&tiny_skia::Stroke {
                         width: stroke.width * self.scale,
                         line_cap: match stroke.line_end {
                             LineEnd::Butt => LineCap::Butt,
                             LineEnd::Round => LineCap::Round,
                         },
                         ..Default::default()
-                    },
-                    Transform::identity(),
-                    None,
+                    }),
                 );
             }
 
             if let Some(fill) = shape.fill {
-                let mut paint = Paint::default();
-                paint.set_color(fill.into());
-                paint.anti_alias = self.antialias;
+                self.draw(&path, Source::Solid(fill), None);
+            }
 
-                self.canvas.fill_path(
-                    &path,
-                    &paint,
-                    FillRule::Winding,
-                    Transform::identity(),
-                    None,
-                );
+            if let Some(pattern) = &shape.pattern {
+                if let Some((pixmap, transform)) = self.pattern_pixmap(pattern) {
+                    let shader = tiny_skia::Pattern::new(
+                        pixmap.as_ref(),
+                        match pattern.repeat {
+                            RepeatMode::Repeat => SpreadMode::Repeat,
+                            RepeatMode::Reflect => SpreadMode::Reflect,
+                            RepeatMode::Pad => SpreadMode::Pad,
+                        },
+                        FilterQuality::Bilinear,
+                        1.0,
+                        transform,
+                    );
+
+                    self.draw(&path, Source::Shader(shader), None);
+                }
+            }
+        }
+    }
+
+    fn begin_group(&mut self, _id: Option<&str>) {
+        self.clip_stack.push(self.clip.clone());
+    }
+
+    fn end_group(&mut self) {
+        if let Some(clip) = self.clip_stack.pop() {
+            self.clip = clip;
+        }
+    }
+
+    fn set_clip(&mut self, clip: Option<&[Vec2]>) {
+        let outer = self.clip_stack.last().cloned().flatten();
+
+        let points = match clip {
+            Some(points) => points,
+            None => {
+                self.clip = outer;
+                return;
+            }
+        };
+
+        let mut path = PathBuilder::new();
+        for (index, point) in points.iter().enumerate() {
+            let point = self.to_image_space(*point);
+            if index == 0 {
+                path.move_to(point.x, point.y);
+            } else {
+                path.line_to(point.x, point.y);
             }
         }
+        path.close();
+
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        let (mut mask, mut paths, outer_bounds) = match outer {
+            Some(outer) => (outer.mask, outer.paths, outer.bounds),
+            None => (
+                ClipMask::new(),
+                Vec::new(),
+                (Vec2::splat(f32::NEG_INFINITY), Vec2::splat(f32::INFINITY)),
+            ),
+        };
+
+        let path = path.finish();
+        let visible = path.as_ref().and_then(|path| {
+            let path = path.clone().transform(self.canvas_transform())?;
+            if mask.is_empty() {
+                mask.set_path(width, height, &path, FillRule::Winding, self.antialias)
+            } else {
+                mask.intersect_path(&path, FillRule::Winding, self.antialias)
+            }
+        });
+
+        // Polygons without an area, or outside of the canvas, hide everything.
+        let bounds = match (visible, path) {
+            (Some(()), Some(path)) => {
+                let bounds = path.bounds();
+                paths.push(path);
+                (
+                    outer_bounds.0.max(Vec2::new(bounds.left(), bounds.top())),
+                    outer_bounds
+                        .1
+                        .min(Vec2::new(bounds.right(), bounds.bottom())),
+                )
+            }
+            _ => {
+                let hidden = PathBuilder::from_rect(Rect::from_xywh(-2.0, -2.0, 1.0, 1.0).unwrap());
+                mask.set_path(width, height, &hidden, FillRule::Winding, false);
+                (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY))
+            }
+        };
+
+        self.clip = Some(Clip {
+            mask,
+            paths,
+            bounds,
+        });
     }
 
     fn finalize(self) -> Self::Output {
-        RgbaImage::from_raw(
-            self.canvas.width(),
-            self.canvas.height(),
-            self.canvas.take(),
-        )
-        .unwrap()
+        if self.float.is_some() {
+            let mut image = RgbaImage::new(self.canvas.width(), self.canvas.height());
+
+            for (pixel, color) in image.pixels_mut().zip(self.into_float_pixels(false)) {
+                *pixel = Rgba([
+                    (color.x.clamp(0.0, 1.0) * 255.0).round() as u8,
+                    (color.y.clamp(0.0, 1.0) * 255.0).round() as u8,
+                    (color.z.clamp(0.0, 1.0) * 255.0).round() as u8,
+                    (color.w * 255.0).round() as u8,
+                ]);
+            }
+
+            return image;
+        }
+
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+
+        // A pixmap always holds exactly `width * height` RGBA pixels.
+        RgbaImage::from_raw(width, height, self.canvas.take()).unwrap()
+    }
+}
+
+impl TryRenderer for SkiaRenderer {
+    type Output = RgbaImage;
+    type Error = RenderError;
+
+    fn try_viewport(&self) -> Option<Viewport> {
+        self.viewport()
+    }
+
+    fn try_render(&mut self, shape: &Shape) -> Result<(), Self::Error> {
+        self.render(shape);
+        Ok(())
+    }
+
+    fn try_begin_group(&mut self, id: Option<&str>) -> Result<(), Self::Error> {
+        self.begin_group(id);
+        Ok(())
+    }
+
+    fn try_end_group(&mut self) -> Result<(), Self::Error> {
+        self.end_group();
+        Ok(())
+    }
+
+    fn try_set_clip(&mut self, clip: Option<&[Vec2]>) -> Result<(), Self::Error> {
+        self.set_clip(clip);
+        Ok(())
+    }
+
+    fn try_finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.finalize())
+    }
+}
+
+/// A [SkiaRenderer] that outputs 16 bits per channel, sRGB encoded.
+///
+/// Shapes are blended at full floating point precision, in the same mode as the [SkiaRenderer] it is created from,
+/// so the output matches 8 bit output apart from its precision.
+#[derive(Clone)]
+pub struct Rgba16SkiaRenderer(SkiaRenderer);
+
+impl Rgba16SkiaRenderer {
+    /// Create a new [Rgba16SkiaRenderer] from the settings of a [SkiaRenderer], including its blending mode.
+    pub fn new(renderer: SkiaRenderer) -> Self {
+        Self(renderer.with_float_pixels())
+    }
+}
+
+impl Renderer for Rgba16SkiaRenderer {
+    type Output = ImageBuffer<Rgba<u16>, Vec<u16>>;
+
+    fn render(&mut self, shape: &Shape) {
+        self.0.render(shape)
+    }
+
+    fn begin_group(&mut self, id: Option<&str>) {
+        self.0.begin_group(id)
+    }
+
+    fn end_group(&mut self) {
+        self.0.end_group()
+    }
+
+    fn set_clip(&mut self, clip: Option<&[Vec2]>) {
+        self.0.set_clip(clip)
+    }
+
+    fn viewport(&self) -> Option<Viewport> {
+        self.0.viewport()
+    }
+
+    fn finalize(self) -> Self::Output {
+        let (width, height) = (self.0.canvas.width(), self.0.canvas.height());
+        let mut image = ImageBuffer::new(width, height);
+
+        for (pixel, color) in image.pixels_mut().zip(self.0.into_float_pixels(false)) {
+            *pixel = Rgba([
+                (color.x.clamp(0.0, 1.0) * 65535.0).round() as u16,
+                (color.y.clamp(0.0, 1.0) * 65535.0).round() as u16,
+                (color.z.clamp(0.0, 1.0) * 65535.0).round() as u16,
+                (color.w * 65535.0).round() as u16,
+            ]);
+        }
+
+        image
+    }
+}
+
+/// A [SkiaRenderer] that outputs 32 bit floats per channel, in linear light.
+///
+/// Shapes are blended in the same mode as the [SkiaRenderer] it is created from, and converted to linear light afterwards.
+/// Colors are never clamped, so values outside of `0.0..=1.0` survive into the output.
+/// This makes it suitable for high dynamic range images, which can be written with [encode_hdr](Self::encode_hdr).
+#[derive(Clone)]
+pub struct Rgba32FSkiaRenderer(SkiaRenderer);
+
+impl Rgba32FSkiaRenderer {
+    /// Create a new [Rgba32FSkiaRenderer] from the settings of a [SkiaRenderer], including its blending mode.
+    pub fn new(renderer: SkiaRenderer) -> Self {
+        Self(renderer.with_float_pixels())
+    }
+
+    /// Encode an image as Radiance HDR.
+    ///
+    /// The format has no alpha channel, so the image is composited over black.
+    pub fn encode_hdr<W: Write>(image: &Rgba32FImage, writer: W) -> ImageResult<()> {
+        let pixels: Vec<Rgb<f32>> = image
+            .pixels()
+            .map(|pixel| {
+                let [r, g, b, a] = pixel.0;
+                Rgb([r * a, g * a, b * a].map(|channel| channel.max(0.0)))
+            })
+            .collect();
+
+        HdrEncoder::new(writer).encode(&pixels, image.width() as usize, image.height() as usize)
+    }
+}
+
+impl Renderer for Rgba32FSkiaRenderer {
+    type Output = Rgba32FImage;
+
+    fn render(&mut self, shape: &Shape) {
+        self.0.render(shape)
+    }
+
+    fn begin_group(&mut self, id: Option<&str>) {
+        self.0.begin_group(id)
+    }
+
+    fn end_group(&mut self) {
+        self.0.end_group()
+    }
+
+    fn set_clip(&mut self, clip: Option<&[Vec2]>) {
+        self.0.set_clip(clip)
+    }
+
+    fn viewport(&self) -> Option<Viewport> {
+        self.0.viewport()
+    }
+
+    fn finalize(self) -> Self::Output {
+        let (width, height) = (self.0.canvas.width(), self.0.canvas.height());
+        let mut image = Rgba32FImage::new(width, height);
+
+        for (pixel, color) in image.pixels_mut().zip(self.0.into_float_pixels(true)) {
+            *pixel = Rgba(color.to_array());
+        }
+
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, Pattern, Stroke};
+
+    /// Verify that an empty image is an error instead of a panic.
+    #[test]
+    fn zero_size_is_an_error() {
+        assert!(matches!(
+            SkiaRenderer::try_new(UVec2::new(0, 10), None, true, true),
+            Err(RenderError::InvalidSize(size)) if size == UVec2::new(0, 10)
+        ));
+
+        let image = Canvas::default()
+            .try_render(SkiaRenderer::try_new(UVec2::splat(2), None, true, true).unwrap())
+            .unwrap();
+        assert_eq!(image.dimensions(), (2, 2));
+    }
+
+    /// Verify that clips hide what is outside of them, and narrow the clip of their enclosing group.
+    #[test]
+    fn clips_to_polygon() {
+        let square = |size: f32| {
+            vec![
+                Vec2::new(-size, -size),
+                Vec2::new(size, -size),
+                Vec2::new(size, size),
+                Vec2::new(-size, size),
+                Vec2::new(-size, -size),
+            ]
+        };
+
+        for linear in [false, true] {
+            let mut canvas = Canvas::default();
+            canvas.set_clip(vec![
+                Vec2::new(-1.0, -1.0),
+                Vec2::new(0.0, -1.0),
+                Vec2::new(0.0, 1.0),
+                Vec2::new(-1.0, 1.0),
+            ]);
+            canvas.begin_group(None::<String>);
+            canvas.set_clip(vec![
+                Vec2::new(-1.0, 0.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(-1.0, 1.0),
+            ]);
+            canvas.draw_shape(square(1.0), None, Some(Color::red()));
+            canvas.end_group();
+            canvas.draw_shape(square(1.0), None, Some(Color::blue()));
+
+            let image = canvas.render(
+                SkiaRenderer::new(UVec2::splat(4), None, false, true).with_linear_blending(linear),
+            );
+
+            // Inside the group, both clips apply. Afterwards, only the left half is drawn.
+            assert_eq!(image.get_pixel(0, 0), &Rgba([0, 0, 255, 255]));
+            assert_eq!(image.get_pixel(3, 0), &Rgba([0, 0, 0, 0]));
+            assert_eq!(image.get_pixel(3, 3), &Rgba([0, 0, 0, 0]));
+        }
+    }
+
+    /// Verify that an image pattern is tiled across the shape it fills.
+    #[test]
+    fn image_pattern_repeats() {
+        let mut tile = RgbaImage::new(2, 1);
+        tile.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        tile.put_pixel(1, 0, Rgba([0, 0, 255, 255]));
+
+        let mut canvas = Canvas::default();
+        canvas.draw_shape_with_pattern(
+            vec![
+                Vec2::new(-1.0, -1.0),
+                Vec2::new(1.0, -1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(-1.0, 1.0),
+                Vec2::new(-1.0, -1.0),
+            ],
+            None,
+            Pattern::image(tile, (1.0, 2.0)),
+        );
+
+        let image = canvas.render(SkiaRenderer::new(UVec2::splat(4), None, false, true));
+
+        for y in 0..4 {
+            assert_eq!(image.get_pixel(0, y), &Rgba([255, 0, 0, 255]));
+            assert_eq!(image.get_pixel(1, y), &Rgba([0, 0, 255, 255]));
+            assert_eq!(image.get_pixel(2, y), &Rgba([255, 0, 0, 255]));
+            assert_eq!(image.get_pixel(3, y), &Rgba([0, 0, 255, 255]));
+        }
+    }
+
+    /// Verify that blending in linear light gives a brighter result than blending sRGB values, at any bit depth.
+    #[test]
+    fn linear_blending_differs() {
+        let mut canvas = Canvas::default();
+        canvas.draw_rect(
+            Vec2::splat(-1.0),
+            Vec2::splat(1.0),
+            None,
+            Some(Color::white().with_a(0.5)),
+        );
+
+        let srgb = canvas.render(SkiaRenderer::new(
+            UVec2::splat(4),
+            Some(Color::black()),
+            false,
+            true,
+        ));
+        let linear = canvas.render(
+            SkiaRenderer::new(UVec2::splat(4), Some(Color::black()), false, true)
+                .with_linear_blending(true),
+        );
+
+        assert_eq!(srgb.get_pixel(1, 1), &Rgba([128, 128, 128, 255]));
+        assert_eq!(linear.get_pixel(1, 1), &Rgba([188, 188, 188, 255]));
+
+        // Deeper output keeps the blending mode of the renderer it is created from.
+        let renderer = SkiaRenderer::new(UVec2::splat(4), Some(Color::black()), false, true);
+        let deep_srgb = canvas.render(Rgba16SkiaRenderer::new(renderer.clone()));
+        let deep_linear = canvas.render(Rgba16SkiaRenderer::new(
+            renderer.clone().with_linear_blending(true),
+        ));
+        assert_eq!(deep_srgb.get_pixel(1, 1).0, [32768, 32768, 32768, 65535]);
+        assert_eq!(deep_linear.get_pixel(1, 1).0[0] >> 8, 188);
+
+        let float = canvas.render(Rgba32FSkiaRenderer::new(renderer));
+        assert!((float.get_pixel(1, 1).0[0] - srgb_to_linear(0.5)).abs() < 0.0001);
+    }
+
+    /// Verify that blending in linear light draws exactly the same pixels for black on transparent,
+    /// including the tip of a sharp miter join.
+    #[test]
+    fn linear_blending_covers_miters() {
+        let mut canvas = Canvas::default();
+        canvas.draw_shape(
+            vec![
+                Vec2::new(-0.8, -0.4),
+                Vec2::new(0.6, 0.0),
+                Vec2::new(-0.8, 0.4),
+            ],
+            Some(Stroke::new(Color::black(), 0.15, LineEnd::Butt)),
+            None,
+        );
+
+        let renderer = SkiaRenderer::new(UVec2::new(300, 200), None, true, true);
+        let srgb = canvas.render(renderer.clone());
+        let linear = canvas.render(renderer.with_linear_blending(true));
+
+        // The tip of the miter reaches past the end of the stroke's outline.
+        let reach = |image: &RgbaImage| {
+            image
+                .enumerate_pixels()
+                .filter(|(_, _, pixel)| pixel[3] > 0)
+                .map(|(x, _, _)| x)
+                .max()
+        };
+        assert!(reach(&srgb).unwrap() > 230);
+        assert_eq!(reach(&linear), reach(&srgb));
+        assert_eq!(linear, srgb);
+    }
+
+    /// Verify that shapes drawn in linear light through a clip get exactly the same clip as in sRGB.
+    #[test]
+    fn linear_blending_clips_exactly() {
+        let mut canvas = Canvas::default();
+        canvas.set_clip(vec![
+            Vec2::new(-0.9, -0.7),
+            Vec2::new(0.8, -0.4),
+            Vec2::new(0.2, 0.9),
+        ]);
+        canvas.begin_group(None::<String>);
+        canvas.set_clip(vec![
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(0.45, -1.0),
+            Vec2::new(0.45, 1.0),
+            Vec2::new(-1.0, 1.0),
+        ]);
+        for i in 0..40 {
+            let center = Vec2::new((i % 8) as f32 * 0.25 - 0.9, (i / 8) as f32 * 0.4 - 0.8);
+            canvas.draw_circle(center, 0.12, None, Some(Color::black()));
+        }
+        canvas.end_group();
+
+        let renderer = SkiaRenderer::new(UVec2::new(150, 100), None, true, true);
+        let srgb = canvas.render(renderer.clone());
+        let linear = canvas.render(renderer.with_linear_blending(true));
+
+        assert!(srgb.pixels().any(|pixel| pixel[3] == 255));
+        assert_eq!(linear, srgb);
+    }
+
+    /// Verify that float output keeps colors outside of the displayable range, and 16 bit output keeps precision.
+    #[test]
+    fn high_dynamic_range_output() {
+        let mut canvas = Canvas::default();
+        canvas.draw_rect(
+            Vec2::new(-2.0, -1.0),
+            Vec2::new(0.0, 1.0),
+            None,
+            Some(Color::new(2.0, 1.0, 0.5, 1.0)),
+        );
+
+        let background = Color::new(0.5, 0.5, 0.5, 1.0);
+        let renderer = SkiaRenderer::new(UVec2::new(4, 2), Some(background), false, true);
+
+        let float = canvas.render(Rgba32FSkiaRenderer::new(renderer.clone()));
+        let [r, g, b, a] = float.get_pixel(0, 0).0;
+        assert!((r - srgb_to_linear(2.0)).abs() < 0.0001);
+        assert!((g - 1.0).abs() < 0.0001);
+        assert!((b - srgb_to_linear(0.5)).abs() < 0.0001);
+        assert_eq!(a, 1.0);
+
+        let deep = canvas.render(Rgba16SkiaRenderer::new(renderer));
+        assert_eq!(deep.get_pixel(0, 0).0, [65535, 65535, 32768, 65535]);
+        assert_eq!(deep.get_pixel(3, 0).0, [32768, 32768, 32768, 65535]);
+
+        let mut hdr = Vec::new();
+        Rgba32FSkiaRenderer::encode_hdr(&float, &mut hdr).unwrap();
+        assert!(hdr.starts_with(b"#?RADIANCE"));
     }
 }
@@ -0,0 +1,218 @@
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
+
+use super::SvgRenderer;
+use crate::{Affine2, RenderError, Renderer, RgbaImage, Shape, TryRenderer, Vec2};
+
+/// An [SvgRenderer] that writes each shape to an [io::Write] as soon as it is rendered,
+/// instead of building the whole document in memory.
+///
+/// The first error from the writer is kept, and returned from [finalize](Renderer::finalize).
+/// Nothing more is written after an error.
+pub struct StreamingSvgRenderer<W: Write> {
+    renderer: SvgRenderer,
+    writer: W,
+    started: bool,
+    error: Option<RenderError>,
+}
+
+impl<W: Write> StreamingSvgRenderer<W> {
+    /// Create a new [StreamingSvgRenderer], writing the document that `renderer` would produce into `writer`.
+    ///
+    /// Wrap `writer` in a [BufWriter](std::io::BufWriter) if it is unbuffered, since each shape is written separately.
+    pub fn new(renderer: SvgRenderer, writer: W) -> Self {
+        Self {
+            renderer,
+            writer,
+            started: false,
+            error: None,
+        }
+    }
+
+    /// Run the inner renderer, then write what it produced, unless a previous step failed.
+    fn write_with(&mut self, f: impl FnOnce(&mut SvgRenderer) -> Result<(), RenderError>) {
+        if self.error.is_some() {
+            return;
+        }
+
+        if let Err(error) = f(&mut self.renderer) {
+            self.error = Some(error);
+        } else if let Err(error) = self.flush_document() {
+            self.error = Some(error.into());
+        }
+    }
+
+    /// Take the first error, if there was one.
+    fn take_error(&mut self) -> Result<(), RenderError> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Write anything the inner renderer has produced, starting with the header.
+    fn flush_document(&mut self) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            self.writer.write_all(self.renderer.header().as_bytes())?;
+        }
+
+        let document = self.renderer.take_document();
+        self.writer.write_all(document.as_bytes())
+    }
+}
+
+impl<W: Write> Renderer for StreamingSvgRenderer<W> {
+    type Output = io::Result<W>;
+
+    /// Patterns that can't be encoded are left out, like with [SvgRenderer].
+    fn render(&mut self, shape: &Shape) {
+        self.write_with(|renderer| {
+            renderer.render(shape);
+            Ok(())
+        });
+    }
+
+    fn render_markers(&mut self, shape: &Shape) {
+        self.write_with(|renderer| {
+            renderer.render_markers(shape);
+            Ok(())
+        });
+    }
+
+    fn begin_group(&mut self, id: Option<&str>) {
+        self.write_with(|renderer| {
+            renderer.begin_group(id);
+            Ok(())
+        });
+    }
+
+    fn end_group(&mut self) {
+        self.write_with(|renderer| {
+            renderer.end_group();
+            Ok(())
+        });
+    }
+
+    fn set_clip(&mut self, clip: Option<&[Vec2]>) {
+        self.write_with(|renderer| {
+            renderer.set_clip(clip);
+            Ok(())
+        });
+    }
+
+    /// Images that can't be encoded are left out, like with [SvgRenderer].
+    fn draw_image(&mut self, image: &Arc<RgbaImage>, transform: Affine2) {
+        self.write_with(|renderer| {
+            renderer.draw_image(image, transform);
+            Ok(())
+        });
+    }
+
+    fn finalize(mut self) -> Self::Output {
+        match self.error {
+            Some(RenderError::Io(error)) => return Err(error),
+            Some(error) => return Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+            None => (),
+        }
+
+        self.renderer.close_groups();
+        self.flush_document()?;
+        self.writer.write_all(self.renderer.footer().as_bytes())?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> TryRenderer for StreamingSvgRenderer<W> {
+    type Output = W;
+    type Error = RenderError;
+
+    fn try_render(&mut self, shape: &Shape) -> Result<(), Self::Error> {
+        self.write_with(|renderer| renderer.write_shape(shape));
+        self.take_error()
+    }
+
+    fn try_render_markers(&mut self, shape: &Shape) -> Result<(), Self::Error> {
+        self.render_markers(shape);
+        self.take_error()
+    }
+
+    fn try_begin_group(&mut self, id: Option<&str>) -> Result<(), Self::Error> {
+        self.begin_group(id);
+        self.take_error()
+    }
+
+    fn try_end_group(&mut self) -> Result<(), Self::Error> {
+        self.end_group();
+        self.take_error()
+    }
+
+    fn try_set_clip(&mut self, clip: Option<&[Vec2]>) -> Result<(), Self::Error> {
+        self.set_clip(clip);
+        self.take_error()
+    }
+
+    fn try_draw_image(
+        &mut self,
+        image: &Arc<RgbaImage>,
+        transform: Affine2,
+    ) -> Result<(), Self::Error> {
+        self.write_with(|renderer| renderer.write_image(image, transform));
+        self.take_error()
+    }
+
+    fn try_finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.finalize()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, Color, Vec2};
+
+    /// Verify that streamed output matches the in-memory renderer, and that write errors are returned.
+    #[test]
+    fn matches_in_memory_output() {
+        let mut canvas = Canvas::default();
+        canvas.draw_circle((0.0, 0.0), 0.5, None, Some(Color::red()));
+        canvas.draw_triangle(
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (0.0, 1.0),
+            None,
+            Some(Color::blue()),
+        );
+
+        let renderer = || {
+            SvgRenderer::new(Vec2::splat(100.0), Some(Color::white()), false, true, 32)
+                .with_title("Streaming")
+        };
+
+        let streamed = canvas
+            .render(StreamingSvgRenderer::new(renderer(), Vec::new()))
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(streamed).unwrap(),
+            canvas.render(renderer())
+        );
+
+        let mut full = [0u8; 16];
+        let error = canvas
+            .render(StreamingSvgRenderer::new(renderer(), &mut full[..]))
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+
+        let mut full = [0u8; 16];
+        let error = canvas
+            .try_render(StreamingSvgRenderer::new(renderer(), &mut full[..]))
+            .unwrap_err();
+        assert!(
+            matches!(error, RenderError::Io(error) if error.kind() == io::ErrorKind::WriteZero)
+        );
+    }
+}
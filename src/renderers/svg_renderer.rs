@@ -1,18 +1,44 @@
-use glam::Vec2;
+use glam::{Affine2, Mat2, Vec2};
+use image::{codecs::png::PngEncoder, ColorType, ImageEncoder, RgbaImage};
 
-use crate::{Color, LineEnd, Renderer, Shape};
-use std::fmt::Write;
+use crate::marker::Placement;
+use crate::{
+    Color, LineEnd, Marker, Page, Pattern, PatternTile, RenderError, Renderer, RepeatMode, Shape,
+    Stroke, TryRenderer, Unit,
+};
+use std::{fmt::Write, sync::Arc};
 
 /// A renderer for Scalable Vector Graphics.
 ///
 /// Unless a shape approximates a circle, it will be drawn as either a polygon or a polyline.
 /// If it does approximate a circle and meets [circle_vertex_threshold](SvgRenderer), it will be drawn as a circle.
+///
+/// By default, coordinates are written in pixels. Use [with_view_box](Self::with_view_box) to write them in Camera Space instead,
+/// so the document scales to whatever size it is displayed at.
 #[derive(Clone)]
 pub struct SvgRenderer {
+    size: Vec2,
+    dimensions: (String, String),
+    page_view_box: bool,
+    background: Option<Color>,
     scale: f32,
     center_offset: Vec2,
-    ints_only: bool,
+    view_box: bool,
+    precision: Option<usize>,
+    pretty: bool,
+    title: Option<String>,
+    description: Option<String>,
     circle_vertex_threshold: usize,
+    pattern_count: usize,
+    clip_count: usize,
+    native_markers: bool,
+    marker_count: usize,
+    /// Whether the markers at the ends of the last shape were written as `<marker>` definitions.
+    marked: bool,
+    /// Whether a `<g>` applying a clip is open in the current group.
+    clipped: bool,
+    /// Whether the enclosing group had a clip open, for each open group.
+    groups: Vec<bool>,
     document: String,
 }
 
@@ -29,22 +55,57 @@ impl SvgRenderer {
         preserve_height: bool,
         circle_vertex_threshold: usize,
     ) -> Self {
-        let mut document = format!(
-            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
-            size.x, size.y
-        );
+        Self::with_dimensions(
+            size,
+            (size.x.to_string(), size.y.to_string()),
+            false,
+            background,
+            ints_only,
+            preserve_height,
+            circle_vertex_threshold,
+        )
+    }
 
-        if let Some(background) = background {
-            write!(
-                document,
-                "<rect fill=\"{}\" width=\"{}\" height=\"{}\"/>",
-                background.as_hex(false),
-                size.x,
-                size.y
-            )
-            .unwrap();
-        }
+    /// Creates a new [SvgRenderer] that fills a [Page].
+    ///
+    /// The document's `width` and `height` are written in the page's unit, like `210mm`,
+    /// and a `viewBox` maps them to the page's size in pixels at its DPI.
+    pub fn from_page(
+        page: Page,
+        background: Option<Color>,
+        ints_only: bool,
+        preserve_height: bool,
+        circle_vertex_threshold: usize,
+    ) -> Self {
+        let size = page.size_in(Unit::Px);
+        let suffix = match page.unit() {
+            Unit::Px => "",
+            unit => unit.suffix(),
+        };
+
+        Self::with_dimensions(
+            size,
+            (
+                format!("{}{}", page.size().x, suffix),
+                format!("{}{}", page.size().y, suffix),
+            ),
+            true,
+            background,
+            ints_only,
+            preserve_height,
+            circle_vertex_threshold,
+        )
+    }
 
+    fn with_dimensions(
+        size: Vec2,
+        dimensions: (String, String),
+        page_view_box: bool,
+        background: Option<Color>,
+        ints_only: bool,
+        preserve_height: bool,
+        circle_vertex_threshold: usize,
+    ) -> Self {
         let (scale, center_offset) = if preserve_height {
             let scale = size.y / 2.0;
             (scale, Vec2::new(size.x / 2.0 / scale, 1.0))
@@ -60,33 +121,244 @@ impl SvgRenderer {
         };
 
         Self {
+            size,
+            dimensions,
+            page_view_box,
+            background,
             scale,
             center_offset,
-            ints_only,
+            view_box: false,
+            precision: if ints_only { Some(0) } else { None },
+            pretty: false,
+            title: None,
+            description: None,
             circle_vertex_threshold,
-            document,
+            pattern_count: 0,
+            clip_count: 0,
+            native_markers: false,
+            marker_count: 0,
+            marked: false,
+            clipped: false,
+            groups: Vec::new(),
+            document: String::new(),
         }
     }
-}
 
-impl Renderer for SvgRenderer {
-    type Output = String;
+    /// Write coordinates in Camera Space, with a `viewBox` that frames the camera, consuming the parent.
+    ///
+    /// The document keeps its `width` and `height`, but no longer depends on them to place shapes.
+    #[inline]
+    pub fn with_view_box(mut self, view_box: bool) -> Self {
+        self.view_box = view_box;
+        self
+    }
 
-    fn render(&mut self, shape: &Shape) {
+    /// Round every number to `precision` decimal places, consuming the parent.
+    ///
+    /// `None` writes numbers at full precision. This overrides `ints_only`, which is the same as `Some(0)`.
+    #[inline]
+    pub fn with_precision(mut self, precision: Option<usize>) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Put each element on its own indented line, consuming the parent.
+    #[inline]
+    pub fn with_pretty_print(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Set the document's `<title>`, consuming the parent.
+    #[inline]
+    pub fn with_title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the document's `<desc>`, consuming the parent.
+    #[inline]
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Write the markers at the ends of shapes as `<marker>` definitions, consuming the parent.
+    ///
+    /// Markers in the middle of shapes are still written as shapes of their own,
+    /// since SVG can only place those at every point.
+    #[inline]
+    pub fn with_native_markers(mut self, native_markers: bool) -> Self {
+        self.native_markers = native_markers;
+        self
+    }
+
+    /// Get the document written so far.
+    pub(crate) fn document_mut(&mut self) -> &mut String {
+        &mut self.document
+    }
+
+    /// Start a new top-level element, placing it on its own line if pretty printing.
+    pub(crate) fn begin_element(&mut self) {
+        if self.pretty {
+            self.document.push_str("\n  ");
+        }
+    }
+
+    /// Format a number at the configured precision.
+    pub(crate) fn number(&self, value: f32) -> String {
+        format_number(value, self.precision)
+    }
+
+    /// Format a transform as the arguments of an SVG `matrix()`.
+    ///
+    /// Only the translation is rounded to the configured precision, since rounding the rest would distort whatever it transforms.
+    fn matrix(&self, transform: Affine2) -> String {
+        format!(
+            "{} {} {} {} {} {}",
+            format_number(transform.matrix2.x_axis.x, None),
+            format_number(transform.matrix2.x_axis.y, None),
+            format_number(transform.matrix2.y_axis.x, None),
+            format_number(transform.matrix2.y_axis.y, None),
+            self.number(transform.translation.x),
+            self.number(transform.translation.y)
+        )
+    }
+
+    /// Write everything that comes before the shapes: the root element, metadata and background.
+    pub(crate) fn header(&self) -> String {
+        let mut document = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\"",
+            self.dimensions.0, self.dimensions.1
+        );
+
+        let (min, size) = self.visible_region();
+        if self.view_box || self.page_view_box {
+            write!(
+                document,
+                " viewBox=\"{} {} {} {}\"",
+                self.number(min.x),
+                self.number(min.y),
+                self.number(size.x),
+                self.number(size.y)
+            )
+            .unwrap();
+        }
+        document.push('>');
+
+        let indent = if self.pretty { "\n  " } else { "" };
+
+        if let Some(title) = &self.title {
+            write!(document, "{}<title>{}</title>", indent, escape_xml(title)).unwrap();
+        }
+
+        if let Some(description) = &self.description {
+            write!(
+                document,
+                "{}<desc>{}</desc>",
+                indent,
+                escape_xml(description)
+            )
+            .unwrap();
+        }
+
+        if let Some(background) = self.background {
+            write!(
+                document,
+                "{}<rect fill=\"{}\"",
+                indent,
+                background.as_hex(false)
+            )
+            .unwrap();
+            if min != Vec2::ZERO {
+                write!(
+                    document,
+                    " x=\"{}\" y=\"{}\"",
+                    self.number(min.x),
+                    self.number(min.y)
+                )
+                .unwrap();
+            }
+            write!(
+                document,
+                " width=\"{}\" height=\"{}\"/>",
+                self.number(size.x),
+                self.number(size.y)
+            )
+            .unwrap();
+        }
+
+        document
+    }
+
+    /// Write everything that comes after the shapes.
+    pub(crate) fn footer(&self) -> &'static str {
+        if self.pretty {
+            "\n</svg>"
+        } else {
+            "</svg>"
+        }
+    }
+
+    /// Close every open group and clip.
+    pub(crate) fn close_groups(&mut self) {
+        while !self.groups.is_empty() {
+            self.end_group();
+        }
+        self.close_clip();
+    }
+
+    /// Close the `<g>` applying the current clip, if there is one.
+    fn close_clip(&mut self) {
+        if self.clipped {
+            self.clipped = false;
+            self.begin_element();
+            self.document.push_str("</g>");
+        }
+    }
+
+    /// Take the shapes written so far, leaving the document empty.
+    pub(crate) fn take_document(&mut self) -> String {
+        std::mem::take(&mut self.document)
+    }
+
+    /// Get the transform from Camera Space to Image Space.
+    pub(crate) fn image_transform(&self) -> Affine2 {
+        if self.view_box {
+            Affine2::from_mat2(Mat2::from_diagonal(Vec2::new(1.0, -1.0)))
+        } else {
+            Affine2::from_mat2_translation(
+                Mat2::from_diagonal(Vec2::new(self.scale, -self.scale)),
+                self.center_offset * self.scale,
+            )
+        }
+    }
+
+    /// Get the region of Image Space that is visible, as `(min, size)`.
+    fn visible_region(&self) -> (Vec2, Vec2) {
+        if self.view_box {
+            (-self.center_offset, self.center_offset * 2.0)
+        } else {
+            (Vec2::ZERO, self.size)
+        }
+    }
+
+    /// Write a shape to the document.
+    pub(crate) fn write_shape(&mut self, shape: &Shape) -> Result<(), RenderError> {
         if !shape.is_drawable() {
-            return;
+            return Ok(());
         }
 
+        let image_transform = self.image_transform();
+        let unit_scale = image_transform.matrix2.x_axis.x;
+
         // Check if shape approximates a circle, if so, render it as such.
         let is_circle = if shape.points.len() >= self.circle_vertex_threshold && shape.is_polygon()
         {
             let center = shape.points.iter().sum::<Vec2>() / shape.points.len() as f32;
             let d = center.distance(shape.points[0]);
 
-            let mut is_circle = Some((
-                (Vec2::new(center.x, -center.y) + self.center_offset) * self.scale,
-                d * self.scale,
-            ));
+            let mut is_circle = Some((image_transform.transform_point2(center), d * unit_scale));
             for point in &shape.points {
                 if center.distance(*point) - d > d * 0.1 {
                     is_circle = None;
@@ -98,72 +370,693 @@ impl Renderer for SvgRenderer {
             None
         };
 
-        if shape.points.len() > 3 && shape.is_polygon() {
+        let mut geometry = String::new();
+
+        let tag = if shape.points.len() > 3 && shape.is_polygon() {
             if let Some((circle_center, circle_radius)) = is_circle {
                 write!(
-                    self.document,
-                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}",
-                    circle_center.x, circle_center.y, circle_radius
+                    geometry,
+                    " cx=\"{}\" cy=\"{}\" r=\"{}",
+                    self.number(circle_center.x),
+                    self.number(circle_center.y),
+                    self.number(circle_radius)
                 )
                 .unwrap();
+                "circle"
             } else {
-                write!(self.document, "<polygon points=\"").unwrap();
+                write!(geometry, " points=\"").unwrap();
+                "polygon"
             }
         } else {
-            write!(self.document, "<polyline points=\"").unwrap();
-        }
+            write!(geometry, " points=\"").unwrap();
+            "polyline"
+        };
 
         if is_circle.is_none() {
-            for point in shape.points.iter().map(|p| {
-                // Transform from Camera Space (range from (-1, -1) to (1, 1)) to Image Space (range from (0, 0) to image size).
-                let p = Vec2::new(p.x, -p.y) + self.center_offset;
-                p * self.scale
-            }) {
-                if self.ints_only {
-                    write!(self.document, "{},{} ", point.x.round(), point.y.round()).unwrap();
-                } else {
-                    write!(self.document, "{},{} ", point.x, point.y).unwrap();
+            // Transform from Camera Space (range from (-1, -1) to (1, 1)) to Image Space (range from (0, 0) to image size).
+            for point in shape
+                .points
+                .iter()
+                .map(|p| image_transform.transform_point2(*p))
+            {
+                write!(
+                    geometry,
+                    "{},{} ",
+                    self.number(point.x),
+                    self.number(point.y)
+                )
+                .unwrap();
+            }
+        }
+
+        // Circles can't have markers, so theirs are drawn as shapes instead.
+        let mut marker_attributes = String::new();
+        self.marked = false;
+        if let (true, Some(stroke), Some(markers), None) =
+            (self.native_markers, shape.stroke, &shape.markers, is_circle)
+        {
+            for (attribute, marker) in [
+                ("marker-start", &markers.start),
+                ("marker-end", &markers.end),
+            ] {
+                if let Some(marker) = marker {
+                    let id = self.write_marker(marker, markers.scale, stroke);
+                    write!(marker_attributes, " {}=\"url(#{})\"", attribute, id).unwrap();
                 }
             }
+            self.marked = true;
+        }
+
+        // A shape with only a pattern doesn't need its own element.
+        if shape.pattern.is_none() || shape.stroke.is_some() || shape.fill.is_some() {
+            self.begin_element();
+            write!(self.document, "<{}", tag).unwrap();
+
+            if let Some(id) = &shape.id {
+                write!(self.document, " id=\"{}\"", escape_xml(id)).unwrap();
+            }
+
+            self.document.push_str(&geometry);
+
+            write!(self.document, "\"{} style=\"", marker_attributes).unwrap();
+
+            if let Some(stroke) = shape.stroke {
+                write!(
+                    self.document,
+                    "stroke:{};stroke-width:{};",
+                    stroke.color.as_hex(false),
+                    self.number(stroke.width * unit_scale)
+                )
+                .unwrap();
+
+                if stroke.color.a() != 1.0 {
+                    write!(self.document, "stroke-opacity:{};", stroke.color.a()).unwrap();
+                }
+
+                match stroke.line_end {
+                    LineEnd::Butt => write!(self.document, "stroke-linecap:butt;").unwrap(),
+                    LineEnd::Round => write!(self.document, "stroke-linecap:round;").unwrap(),
+                }
+            }
+
+            if let Some(fill) = shape.fill {
+                write!(self.document, "fill:{};", fill.as_hex(false)).unwrap();
+
+                if fill.a() != 1.0 {
+                    write!(self.document, "fill-opacity:{};", fill.a()).unwrap();
+                }
+            } else {
+                write!(self.document, "fill:none;").unwrap();
+            }
+
+            write!(self.document, "\"/>").unwrap();
+        }
+
+        if let Some(pattern) = &shape.pattern {
+            if pattern.repeat == RepeatMode::Pad {
+                self.write_single_tile(pattern, tag, &geometry)?;
+            } else {
+                let id = self.write_pattern(pattern)?;
+
+                self.begin_element();
+                write!(
+                    self.document,
+                    "<{}{}\" style=\"fill:url(#{});\"/>",
+                    tag, geometry, id
+                )
+                .unwrap();
+            }
         }
 
-        write!(self.document, "\" style=\"").unwrap();
+        Ok(())
+    }
+
+    /// Write an image to the document, with `transform` from its pixels to Camera Space.
+    pub(crate) fn write_image(
+        &mut self,
+        image: &Arc<RgbaImage>,
+        transform: Affine2,
+    ) -> Result<(), RenderError> {
+        let transform = self.image_transform() * transform;
+        let data = png_data_uri(image)?;
 
-        if let Some(stroke) = shape.stroke {
+        self.begin_element();
+        write!(
+            self.document,
+            "<image width=\"{}\" height=\"{}\" transform=\"matrix({})\" href=\"{}\"/>",
+            image.width(),
+            image.height(),
+            self.matrix(transform),
+            data
+        )
+        .unwrap();
+
+        Ok(())
+    }
+
+    /// Write a `<marker>` definition to the document, returning its id.
+    fn write_marker(&mut self, marker: &Marker, scale: f32, stroke: Stroke) -> String {
+        let id = format!("barium-marker-{}", self.marker_count);
+        self.marker_count += 1;
+
+        // Markers are laid out in stroke widths, with y flipped like the rest of Image Space.
+        let mut points = String::new();
+        for point in marker.outline() {
             write!(
-                self.document,
-                "stroke:{};stroke-width:{};",
-                stroke.color.as_hex(false),
-                stroke.width * self.scale
+                points,
+                "{},{} ",
+                self.number(point.x * scale),
+                self.number(-point.y * scale)
             )
             .unwrap();
+        }
+
+        let (tag, mut style) = if marker.is_stroked() {
+            let line_end = match stroke.line_end {
+                LineEnd::Butt => "butt",
+                LineEnd::Round => "round",
+            };
+            (
+                "polyline",
+                format!(
+                    "fill:none;stroke:{};stroke-width:1;stroke-linecap:{};",
+                    stroke.color.as_hex(false),
+                    line_end
+                ),
+            )
+        } else {
+            ("polygon", format!("fill:{};", stroke.color.as_hex(false)))
+        };
+
+        // Markers don't inherit the opacity of the shape they are drawn on.
+        if stroke.color.a() != 1.0 {
+            let property = if marker.is_stroked() {
+                "stroke-opacity"
+            } else {
+                "fill-opacity"
+            };
+            write!(style, "{}:{};", property, stroke.color.a()).unwrap();
+        }
+
+        self.begin_element();
+        write!(
+            self.document,
+            "<defs><marker id=\"{}\" markerUnits=\"strokeWidth\" orient=\"auto-start-reverse\" overflow=\"visible\"><{} points=\"{}\" style=\"{}\"/></marker></defs>",
+            id, tag, points, style
+        )
+        .unwrap();
+
+        id
+    }
+
+    /// Write the contents of a single pattern tile, filling the rectangle from the origin to its size.
+    fn pattern_tile(&self, pattern: &Pattern) -> Result<String, RenderError> {
+        let size = pattern.size;
+        Ok(match &pattern.tile {
+            PatternTile::Image(image) => format!(
+                "<image width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\" href=\"{}\"/>",
+                self.number(size.x),
+                self.number(size.y),
+                png_data_uri(image)?
+            ),
+            PatternTile::Canvas(canvas) => canvas.try_render(
+                SvgRenderer::new(size, None, false, true, self.circle_vertex_threshold)
+                    .with_precision(self.precision),
+            )?,
+        })
+    }
+
+    /// Write a `<pattern>` definition to the document, returning its id.
+    fn write_pattern(&mut self, pattern: &Pattern) -> Result<String, RenderError> {
+        let size = pattern.size;
+        let tile = self.pattern_tile(pattern)?;
+
+        let id = format!("barium-pattern-{}", self.pattern_count);
+        self.pattern_count += 1;
+
+        // SVG patterns always repeat, so reflecting has to be built out of a larger tile.
+        let (tile_size, contents) = match pattern.repeat {
+            RepeatMode::Reflect => (
+                size * 2.0,
+                format!(
+                    "{tile}<g transform=\"matrix(-1 0 0 1 {x} 0)\">{tile}</g><g transform=\"matrix(1 0 0 -1 0 {y})\">{tile}</g><g transform=\"matrix(-1 0 0 -1 {x} {y})\">{tile}</g>",
+                    x = self.number(size.x * 2.0),
+                    y = self.number(size.y * 2.0),
+                    tile = tile
+                ),
+            ),
+            _ => (size, tile),
+        };
+
+        let transform = self.image_transform() * pattern.tile_pixel_transform(size);
+
+        self.begin_element();
+        write!(
+            self.document,
+            "<defs><pattern id=\"{}\" patternUnits=\"userSpaceOnUse\" width=\"{}\" height=\"{}\" patternTransform=\"matrix({})\">{}</pattern></defs>",
+            id,
+            self.number(tile_size.x),
+            self.number(tile_size.y),
+            self.matrix(transform),
+            contents
+        )
+        .unwrap();
+
+        Ok(id)
+    }
+
+    /// Write a single tile of a pattern, clipped to the shape `<{tag}{geometry}"/>`.
+    ///
+    /// SVG can't extend the edges of a tile, so this is how [RepeatMode::Pad] is drawn.
+    fn write_single_tile(
+        &mut self,
+        pattern: &Pattern,
+        tag: &str,
+        geometry: &str,
+    ) -> Result<(), RenderError> {
+        let tile = self.pattern_tile(pattern)?;
+
+        let id = format!("barium-clip-{}", self.clip_count);
+        self.clip_count += 1;
+
+        let transform = self.image_transform() * pattern.tile_pixel_transform(pattern.size);
 
-            if stroke.color.a() != 1.0 {
-                write!(self.document, "stroke-opacity:{};", stroke.color.a()).unwrap();
+        self.begin_element();
+        write!(
+            self.document,
+            "<defs><clipPath id=\"{}\"><{}{}\"/></clipPath></defs>",
+            id, tag, geometry
+        )
+        .unwrap();
+        self.begin_element();
+        write!(
+            self.document,
+            "<g clip-path=\"url(#{})\"><g transform=\"matrix({})\">{}</g></g>",
+            id,
+            self.matrix(transform),
+            tile
+        )
+        .unwrap();
+
+        Ok(())
+    }
+}
+
+/// Format a number, rounded to `precision` decimal places if given.
+///
+/// Halves are rounded away from zero, like [f32::round]. Trailing zeros are trimmed, and negative zero is written as `0`.
+pub(crate) fn format_number(value: f32, precision: Option<usize>) -> String {
+    let mut formatted = match precision {
+        Some(precision) => {
+            let factor = 10f64.powi(precision as i32);
+            format!("{:.*}", precision, (value as f64 * factor).round() / factor)
+        }
+        None => value.to_string(),
+    };
+
+    if formatted.contains('.') {
+        let trimmed = formatted.trim_end_matches('0').trim_end_matches('.').len();
+        formatted.truncate(trimmed);
+    }
+
+    if formatted == "-0" {
+        formatted.remove(0);
+    }
+
+    formatted
+}
+
+/// Escape text for use in XML attributes and content.
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Encode an image as a PNG `data:` URI.
+fn png_data_uri(image: &RgbaImage) -> Result<String, RenderError> {
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png).write_image(
+        image.as_raw(),
+        image.width(),
+        image.height(),
+        ColorType::Rgba8,
+    )?;
+
+    Ok(format!("data:image/png;base64,{}", base64(&png)))
+}
+
+/// Encode bytes as standard, padded base64.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
             }
+        }
+    }
 
-            match stroke.line_end {
-                LineEnd::Butt => write!(self.document, "stroke-linecap:butt;").unwrap(),
-                LineEnd::Round => write!(self.document, "stroke-linecap:round;").unwrap(),
+    encoded
+}
+
+impl Renderer for SvgRenderer {
+    type Output = String;
+
+    /// Patterns that can't be encoded are left out. Use [Canvas::try_render](crate::Canvas::try_render) to find out about them.
+    fn render(&mut self, shape: &Shape) {
+        let _ = self.write_shape(shape);
+    }
+
+    fn render_markers(&mut self, shape: &Shape) {
+        if !self.marked {
+            for marker in shape.marker_shapes() {
+                self.render(&marker);
             }
+        } else if let Some(marker) = shape.marker_shape(Placement::Middle) {
+            self.render(&marker);
         }
+    }
 
-        if let Some(fill) = shape.fill {
-            write!(self.document, "fill:{};", fill.as_hex(false)).unwrap();
+    fn begin_group(&mut self, id: Option<&str>) {
+        self.begin_element();
+        match id {
+            Some(id) => write!(self.document, "<g id=\"{}\">", escape_xml(id)).unwrap(),
+            None => self.document.push_str("<g>"),
+        }
+
+        self.groups.push(self.clipped);
+        self.clipped = false;
+    }
+
+    fn end_group(&mut self) {
+        if let Some(clipped) = self.groups.pop() {
+            self.close_clip();
+            self.begin_element();
+            self.document.push_str("</g>");
+            self.clipped = clipped;
+        }
+    }
+
+    fn set_clip(&mut self, clip: Option<&[Vec2]>) {
+        self.close_clip();
+
+        if let Some(points) = clip {
+            let id = format!("barium-clip-{}", self.clip_count);
+            self.clip_count += 1;
 
-            if fill.a() != 1.0 {
-                write!(self.document, "fill-opacity:{};", fill.a()).unwrap();
+            let image_transform = self.image_transform();
+            let mut polygon = String::new();
+            for point in points.iter().map(|p| image_transform.transform_point2(*p)) {
+                write!(
+                    polygon,
+                    "{},{} ",
+                    self.number(point.x),
+                    self.number(point.y)
+                )
+                .unwrap();
             }
-        } else {
-            write!(self.document, "fill:none;").unwrap();
+
+            self.begin_element();
+            write!(
+                self.document,
+                "<defs><clipPath id=\"{}\"><polygon points=\"{}\"/></clipPath></defs>",
+                id, polygon
+            )
+            .unwrap();
+            self.begin_element();
+            write!(self.document, "<g clip-path=\"url(#{})\">", id).unwrap();
+            self.clipped = true;
         }
+    }
 
-        write!(self.document, "\"/>").unwrap();
+    /// Images that can't be encoded are left out. Use [Canvas::try_render](crate::Canvas::try_render) to find out about them.
+    fn draw_image(&mut self, image: &Arc<RgbaImage>, transform: Affine2) {
+        let _ = self.write_image(image, transform);
     }
 
     fn finalize(mut self) -> Self::Output {
-        write!(self.document, "</svg>").unwrap();
+        self.close_groups();
+
+        let mut document = self.header();
+        document.push_str(&self.document);
+        document.push_str(self.footer());
+
+        document
+    }
+}
+
+/// Fails if an image, or an image in a pattern, can't be encoded.
+impl TryRenderer for SvgRenderer {
+    type Output = String;
+    type Error = RenderError;
+
+    fn try_render(&mut self, shape: &Shape) -> Result<(), Self::Error> {
+        self.write_shape(shape)
+    }
+
+    fn try_render_markers(&mut self, shape: &Shape) -> Result<(), Self::Error> {
+        self.render_markers(shape);
+        Ok(())
+    }
+
+    fn try_begin_group(&mut self, id: Option<&str>) -> Result<(), Self::Error> {
+        self.begin_group(id);
+        Ok(())
+    }
+
+    fn try_end_group(&mut self) -> Result<(), Self::Error> {
+        self.end_group();
+        Ok(())
+    }
+
+    fn try_set_clip(&mut self, clip: Option<&[Vec2]>) -> Result<(), Self::Error> {
+        self.set_clip(clip);
+        Ok(())
+    }
+
+    fn try_draw_image(
+        &mut self,
+        image: &Arc<RgbaImage>,
+        transform: Affine2,
+    ) -> Result<(), Self::Error> {
+        self.write_image(image, transform)
+    }
+
+    fn try_finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PaperSize;
+
+    /// Verify that a page in physical units is written with matching dimensions and a viewBox.
+    #[test]
+    fn page_dimensions() {
+        let svg = SvgRenderer::from_page(
+            Page::paper(PaperSize::A4).with_dpi(25.4),
+            None,
+            false,
+            true,
+            32,
+        )
+        .finalize();
+
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"210mm\" height=\"297mm\" viewBox=\"0 0 210 297\">"
+        ));
+    }
+
+    /// Verify that view box output uses Camera Space, with rounded numbers, escaped metadata and one element per line,
+    /// and that shapes outside of the view box are still written.
+    #[test]
+    fn view_box_and_formatting() {
+        let mut canvas = crate::Canvas::default();
+        canvas.draw_line(
+            (0.0, 0.0),
+            (0.123456, -0.5),
+            Some(crate::Stroke::new(Color::black(), 0.1, LineEnd::Butt)),
+            None,
+        );
 
-        self.document
+        let svg = canvas.render(
+            SvgRenderer::new(
+                Vec2::new(200.0, 100.0),
+                Some(Color::white()),
+                false,
+                true,
+                32,
+            )
+            .with_view_box(true)
+            .with_precision(Some(2))
+            .with_pretty_print(true)
+            .with_title("Fish & <Chips>"),
+        );
+
+        assert_eq!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"100\" viewBox=\"-2 -1 4 2\">\n  \
+             <title>Fish &amp; &lt;Chips&gt;</title>\n  \
+             <rect fill=\"#FFFFFF\" x=\"-2\" y=\"-1\" width=\"4\" height=\"2\"/>\n  \
+             <polyline points=\"0,0 0.12,0.5 \" style=\"stroke:#000000;stroke-width:0.1;stroke-linecap:butt;fill:none;\"/>\n\
+             </svg>"
+        );
+
+        let mut canvas = crate::Canvas::default();
+        canvas.draw_circle((10.0, 0.0), 0.5, None, Some(Color::red()));
+        let svg = canvas.render(SvgRenderer::new(Vec2::splat(100.0), None, false, true, 32));
+        assert!(svg.contains("<circle cx=\"550"));
+
+        assert_eq!(format_number(-0.001, Some(2)), "0");
+        assert_eq!(format_number(2.5, Some(0)), "3");
+        assert_eq!(format_number(-0.125, Some(2)), "-0.13");
+        assert_eq!(format_number(1.25, None), "1.25");
+    }
+
+    /// Verify that groups and clips are written as nested elements, and closed at the end of the document.
+    #[test]
+    fn groups_and_clips() {
+        let mut canvas = crate::Canvas::default();
+        canvas.begin_group(Some("eyes"));
+        canvas.set_clip(vec![
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 0.0),
+        ]);
+        canvas.draw_line((0.0, 0.0), (1.0, 0.0), None, None);
+        canvas.begin_group(None::<String>);
+        canvas.draw_image_absolute(RgbaImage::new(1, 1), (-1.0, 1.0), (1.0, 1.0));
+
+        let svg = canvas.render(SvgRenderer::new(Vec2::splat(2.0), None, false, true, 32));
+
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"2\" height=\"2\">\
+             <g id=\"eyes\">\
+             <defs><clipPath id=\"barium-clip-0\"><polygon points=\"0,0 2,0 1,1 \"/></clipPath></defs>\
+             <g clip-path=\"url(#barium-clip-0)\">\
+             <polyline points=\"1,1 2,1 \" style=\"fill:none;\"/>\
+             <g><image width=\"1\" height=\"1\" transform=\"matrix(1 0 0 1 0 0)\" href=\"data:image/png;base64,"
+        ));
+        assert!(svg.ends_with("\"/></g></g></g></svg>"));
+    }
+
+    /// Verify that a padded pattern is written as a single tile clipped to its shape, rather than a `<pattern>`.
+    #[test]
+    fn padded_pattern() {
+        let mut canvas = crate::Canvas::default();
+        canvas.draw_shape_with_pattern(
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(0.0, 0.0),
+            ],
+            None,
+            Pattern::image(RgbaImage::new(1, 1), (1.0, 1.0)).with_repeat(RepeatMode::Pad),
+        );
+
+        let svg = canvas.render(SvgRenderer::new(Vec2::splat(2.0), None, false, true, 32));
+        assert!(svg.contains(
+            "<defs><clipPath id=\"barium-clip-0\"><polygon points=\"1,1 2,1 2,0 1,1 \"/></clipPath></defs>\
+             <g clip-path=\"url(#barium-clip-0)\"><g transform=\"matrix(1 0 0 1 1 0)\"><image width=\"1\" height=\"1\""
+        ));
+        assert!(!svg.contains("<pattern"));
+    }
+
+    /// Verify that an image that can't be encoded is an error when rendering fallibly, and left out otherwise.
+    #[test]
+    fn empty_image_is_an_error() {
+        let mut canvas = crate::Canvas::default();
+        canvas.draw_image_absolute(RgbaImage::new(0, 0), (-1.0, 1.0), (1.0, 1.0));
+
+        let renderer = || SvgRenderer::new(Vec2::splat(2.0), None, false, true, 32);
+        assert!(matches!(
+            canvas.try_render(renderer()),
+            Err(RenderError::Image(_))
+        ));
+        assert!(!canvas.render(renderer()).contains("<image"));
+    }
+
+    /// Verify that markers at the ends can be written as `<marker>` definitions at the configured precision,
+    /// while the middle is always a shape.
+    #[test]
+    fn native_markers() {
+        let mut canvas = crate::Canvas::default();
+        canvas.draw_line(
+            (0.0, 0.0),
+            (1.0, 0.0),
+            Some(crate::Stroke::new(Color::black(), 0.1, LineEnd::Butt)),
+            None,
+        );
+        canvas.set_last_shape_markers(
+            crate::Markers::end(Marker::Arrow).with_middle(Some(Marker::Bar)),
+        );
+
+        let svg = canvas.render(
+            SvgRenderer::new(Vec2::splat(2.0), None, false, true, 32).with_native_markers(true),
+        );
+        assert!(svg.contains(
+            "<defs><marker id=\"barium-marker-0\" markerUnits=\"strokeWidth\" orient=\"auto-start-reverse\" overflow=\"visible\">\
+             <polygon points=\"1.5,0 -2.5,-2 -2.5,2 1.5,0 \" style=\"fill:#000000;\"/></marker></defs>"
+        ));
+        assert!(svg.contains(" marker-end=\"url(#barium-marker-0)\""));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(!svg.contains("<polygon points=\"2"));
+
+        let svg = canvas.render(
+            SvgRenderer::new(Vec2::splat(2.0), None, true, true, 32).with_native_markers(true),
+        );
+        assert!(svg.contains("<polygon points=\"2,0 -3,-2 -3,2 2,0 \""));
+
+        let svg = canvas.render(SvgRenderer::new(Vec2::splat(2.0), None, false, true, 32));
+        assert!(!svg.contains("<marker"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches("<polygon").count(), 1);
+
+        // Translucent strokes get translucent markers, like the markers drawn as shapes.
+        let line = &mut canvas.as_raw_mut()[0];
+        line.stroke = Some(crate::Stroke::new(
+            Color::black().with_a(0.5),
+            0.1,
+            LineEnd::Butt,
+        ));
+        line.markers = Some(
+            crate::Markers::end(Marker::Arrow)
+                .with_start(Some(Marker::OpenArrow))
+                .with_middle(Some(Marker::Bar)),
+        );
+        let svg = canvas.render(
+            SvgRenderer::new(Vec2::splat(2.0), None, false, true, 32).with_native_markers(true),
+        );
+        assert!(svg.contains("style=\"fill:#000000;fill-opacity:0.5;\"/></marker>"));
+        assert!(svg.contains("stroke-linecap:butt;stroke-opacity:0.5;\"/></marker>"));
+        let shapes = canvas.render(SvgRenderer::new(Vec2::splat(2.0), None, false, true, 32));
+        assert!(shapes.contains("fill:#000000;fill-opacity:0.5;"));
     }
 }
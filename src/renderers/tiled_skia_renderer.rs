@@ -0,0 +1,408 @@
+use glam::{UVec2, Vec2};
+use image::{imageops, GenericImage, RgbaImage};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::skia_renderer::viewport;
+use super::SkiaRenderer;
+use crate::{Color, Page, RenderError, Renderer, Shape, Stroke, TryRenderer, Viewport};
+
+/// A [SkiaRenderer] that splits the image into tiles, and rasterizes each of them separately.
+///
+/// Only the shapes that overlap a tile are drawn into it, and the finished tiles are stitched together at
+/// [finalize](Renderer::finalize). With the `parallel` feature, tiles are rasterized on a thread pool,
+/// which doesn't change the output.
+///
+/// The output is pixel-identical to a [SkiaRenderer] with the same settings. Cutting a path at the border of a tile
+/// would change the coverage of the pixels along it, so each tile is rasterized in a region that is grown to hold
+/// every shape and clip that reaches into it, and then cut out of that. Shapes and clips that span many tiles
+/// are rasterized again for each of them, so this is fastest for scenes made of many small shapes.
+pub struct TiledSkiaRenderer {
+    size: UVec2,
+    background: Option<Color>,
+    antialias: bool,
+    preserve_height: bool,
+    linear_blending: bool,
+    tile_size: u32,
+    scale: f32,
+    center_offset: Vec2,
+    steps: Vec<Step>,
+}
+
+/// Something to replay onto every tile.
+enum Step {
+    /// A shape, with its bounds in Image Space.
+    Shape(Box<Shape>, (Vec2, Vec2)),
+    BeginGroup,
+    EndGroup,
+    SetClip(Option<Vec<Vec2>>),
+}
+
+impl TiledSkiaRenderer {
+    /// The width and height of each tile, unless otherwise specified.
+    pub const DEFAULT_TILE_SIZE: u32 = 512;
+
+    /// Create a new [TiledSkiaRenderer].
+    ///
+    /// See [SkiaRenderer::new].
+    ///
+    /// # Panics
+    ///
+    /// Panics if either dimension of `size` is zero. Use [try_new](Self::try_new) to handle that instead.
+    pub fn new(
+        size: UVec2,
+        background: Option<Color>,
+        antialias: bool,
+        preserve_height: bool,
+    ) -> Self {
+        match Self::try_new(size, background, antialias, preserve_height) {
+            Ok(renderer) => renderer,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Create a new [TiledSkiaRenderer], or a [RenderError::InvalidSize] if either dimension of `size` is zero.
+    pub fn try_new(
+        size: UVec2,
+        background: Option<Color>,
+        antialias: bool,
+        preserve_height: bool,
+    ) -> Result<Self, RenderError> {
+        if size.min_element() == 0 {
+            return Err(RenderError::InvalidSize(size));
+        }
+
+        let (scale, center_offset) = viewport(size, preserve_height);
+
+        Ok(Self {
+            size,
+            background,
+            antialias,
+            preserve_height,
+            linear_blending: false,
+            tile_size: Self::DEFAULT_TILE_SIZE,
+            scale,
+            center_offset,
+            steps: Vec::new(),
+        })
+    }
+
+    /// Create a new [TiledSkiaRenderer] that fills a [Page], rasterized at the page's DPI.
+    ///
+    /// See [SkiaRenderer::from_page].
+    pub fn from_page(
+        page: Page,
+        background: Option<Color>,
+        antialias: bool,
+        preserve_height: bool,
+    ) -> Self {
+        Self::new(page.pixel_size(), background, antialias, preserve_height)
+    }
+
+    /// Modify the width and height of each tile, consuming the parent.
+    ///
+    /// Smaller tiles skip more shapes, but each shape that crosses several tiles is rasterized several times.
+    #[inline]
+    pub fn with_tile_size(mut self, tile_size: u32) -> Self {
+        self.tile_size = tile_size.max(1);
+        self
+    }
+
+    /// Composite shapes in linear light, consuming the parent.
+    ///
+    /// See [SkiaRenderer::with_linear_blending].
+    #[inline]
+    pub fn with_linear_blending(mut self, linear_blending: bool) -> Self {
+        self.linear_blending = linear_blending;
+        self
+    }
+
+    /// Find the bounds of a polygon in Image Space, including anything antialiasing or its stroke could touch.
+    fn bounds(&self, points: &[Vec2], stroke: Option<Stroke>) -> (Vec2, Vec2) {
+        let (min, max) = points.iter().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), point| {
+                let point = (Vec2::new(point.x, -point.y) + self.center_offset) * self.scale;
+                (min.min(point), max.max(point))
+            },
+        );
+
+        // Miter joins can reach past half of the stroke width.
+        let margin = stroke.map_or(0.0, |stroke| stroke.width * self.scale * 2.0) + 2.0;
+
+        (min - margin, max + margin)
+    }
+
+    /// Rasterize the tile with its top left corner at `origin`.
+    fn render_tile(&self, origin: UVec2) -> RgbaImage {
+        let region = (self.size - origin).min(UVec2::splat(self.tile_size));
+        let (min, max) = (origin.as_vec2(), (origin + region).as_vec2());
+        let overlaps = |(other_min, other_max): (Vec2, Vec2)| {
+            other_min.cmplt(max).all() && other_max.cmpgt(min).all()
+        };
+
+        // Grow the rasterized region until no path that reaches into the tile is cut off by it.
+        let (grown_min, grown_max) = self
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                Step::Shape(_, bounds) => Some(*bounds),
+                Step::SetClip(Some(clip)) => Some(self.bounds(clip, None)),
+                _ => None,
+            })
+            .filter(|bounds| overlaps(*bounds))
+            .fold((min, max), |(min, max), (other_min, other_max)| {
+                (min.min(other_min), max.max(other_max))
+            });
+        let grown_min = grown_min.floor().max(Vec2::ZERO).as_uvec2();
+        let grown_max = grown_max.ceil().min(self.size.as_vec2()).as_uvec2();
+
+        let mut renderer = SkiaRenderer::try_new_region(
+            self.size,
+            grown_min,
+            grown_max - grown_min,
+            self.background,
+            self.antialias,
+            self.preserve_height,
+        )
+        .expect("Tiles are never empty")
+        .with_linear_blending(self.linear_blending);
+
+        for step in &self.steps {
+            match step {
+                Step::Shape(shape, bounds) => {
+                    if overlaps(*bounds) {
+                        renderer.render(shape);
+                    }
+                }
+                Step::BeginGroup => renderer.begin_group(None),
+                Step::EndGroup => renderer.end_group(),
+                Step::SetClip(clip) => renderer.set_clip(clip.as_deref()),
+            }
+        }
+
+        let offset = origin - grown_min;
+        imageops::crop_imm(&renderer.finalize(), offset.x, offset.y, region.x, region.y).to_image()
+    }
+}
+
+impl Renderer for TiledSkiaRenderer {
+    type Output = RgbaImage;
+
+    fn viewport(&self) -> Option<Viewport> {
+        Some(Viewport {
+            min: -self.center_offset,
+            max: self.center_offset,
+            resolution: Some(self.scale),
+        })
+    }
+
+    fn render(&mut self, shape: &Shape) {
+        if shape.is_drawable() {
+            self.steps.push(Step::Shape(
+                Box::new(shape.clone()),
+                self.bounds(&shape.points, shape.stroke),
+            ));
+        }
+    }
+
+    fn begin_group(&mut self, _id: Option<&str>) {
+        self.steps.push(Step::BeginGroup);
+    }
+
+    fn end_group(&mut self) {
+        self.steps.push(Step::EndGroup);
+    }
+
+    fn set_clip(&mut self, clip: Option<&[Vec2]>) {
+        self.steps.push(Step::SetClip(clip.map(<[Vec2]>::to_vec)));
+    }
+
+    fn finalize(self) -> Self::Output {
+        let origins: Vec<UVec2> = (0..self.size.y)
+            .step_by(self.tile_size as usize)
+            .flat_map(|y| {
+                (0..self.size.x)
+                    .step_by(self.tile_size as usize)
+                    .map(move |x| UVec2::new(x, y))
+            })
+            .collect();
+
+        #[cfg(feature = "parallel")]
+        let tiles = origins.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let tiles = origins.iter();
+
+        let tiles: Vec<RgbaImage> = tiles.map(|origin| self.render_tile(*origin)).collect();
+
+        let mut image = RgbaImage::new(self.size.x, self.size.y);
+        for (origin, tile) in origins.iter().zip(tiles) {
+            image
+                .copy_from(&tile, origin.x, origin.y)
+                .expect("Tiles are inside of the image");
+        }
+
+        image
+    }
+}
+
+impl TryRenderer for TiledSkiaRenderer {
+    type Output = RgbaImage;
+    type Error = RenderError;
+
+    fn try_viewport(&self) -> Option<Viewport> {
+        self.viewport()
+    }
+
+    fn try_render(&mut self, shape: &Shape) -> Result<(), Self::Error> {
+        self.render(shape);
+        Ok(())
+    }
+
+    fn try_begin_group(&mut self, id: Option<&str>) -> Result<(), Self::Error> {
+        self.begin_group(id);
+        Ok(())
+    }
+
+    fn try_end_group(&mut self) -> Result<(), Self::Error> {
+        self.end_group();
+        Ok(())
+    }
+
+    fn try_set_clip(&mut self, clip: Option<&[Vec2]>) -> Result<(), Self::Error> {
+        self.set_clip(clip);
+        Ok(())
+    }
+
+    fn try_finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, LineEnd, Pattern, Stroke};
+
+    /// Build a deterministic scatter of shapes, of every kind, that cross tiles.
+    fn scatter(canvas: &mut Canvas, count: usize) {
+        let mut state = 0x2545_f491_u32;
+        let mut random = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f32 / u32::MAX as f32 * 2.4 - 1.2
+        };
+
+        for i in 0..count {
+            let color = Color::new(random().abs(), random().abs(), random().abs(), 0.75);
+            let stroke = Some(Stroke::new(color, random().abs() * 0.05, LineEnd::Round));
+
+            match i % 4 {
+                0 => canvas.draw_circle((random(), random()), random().abs() * 0.4, stroke, None),
+                1 => canvas.draw_triangle(
+                    (random(), random()),
+                    (random(), random()),
+                    (random(), random()),
+                    None,
+                    Some(color),
+                ),
+                2 => canvas.draw_line((random(), random()), (random(), random()), stroke, None),
+                _ => canvas.draw_rect(
+                    (random(), random()),
+                    (random(), random()),
+                    stroke,
+                    Some(color),
+                ),
+            }
+        }
+    }
+
+    /// Render a canvas both with and without tiles, for every combination of antialiasing and linear blending.
+    fn render_both(canvas: &Canvas) -> Vec<(RgbaImage, RgbaImage)> {
+        let size = UVec2::new(301, 187);
+
+        [(false, false), (false, true), (true, false), (true, true)]
+            .iter()
+            .map(|&(linear, antialias)| {
+                (
+                    canvas.render(
+                        SkiaRenderer::new(size, Some(Color::white()), antialias, true)
+                            .with_linear_blending(linear),
+                    ),
+                    canvas.render(
+                        TiledSkiaRenderer::new(size, Some(Color::white()), antialias, true)
+                            .with_tile_size(64)
+                            .with_linear_blending(linear),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    /// Verify that tiled output is identical to rendering the whole image at once, for axis aligned shapes.
+    #[test]
+    fn matches_single_pixmap() {
+        let mut canvas = Canvas::default();
+        for i in 0..20 {
+            let offset = i as f32 * 0.13 - 1.2;
+            canvas.draw_rect(
+                (offset, offset * 0.7),
+                (offset + 0.731, offset * 0.7 - 0.417),
+                Some(Stroke::new(Color::blue(), 0.0123, LineEnd::Round)),
+                Some(Color::new(1.0, 0.0, 0.0, 0.5)),
+            );
+        }
+        canvas.draw_shape_with_pattern(
+            vec![
+                Vec2::new(-1.1, -0.9),
+                Vec2::new(1.3, -0.9),
+                Vec2::new(1.3, 0.8),
+                Vec2::new(-1.1, 0.8),
+                Vec2::new(-1.1, -0.9),
+            ],
+            None,
+            Pattern::image(
+                RgbaImage::from_pixel(3, 2, image::Rgba([20, 200, 90, 128])),
+                (0.3, 0.2),
+            ),
+        );
+
+        for (expected, tiled) in render_both(&canvas) {
+            assert!(expected == tiled);
+        }
+    }
+
+    /// Verify that tiled output is identical to rendering the whole image at once, for sloped edges and clips
+    /// that cross tiles.
+    #[test]
+    fn matches_single_pixmap_across_tiles() {
+        let mut canvas = Canvas::default();
+        scatter(&mut canvas, 60);
+        canvas.set_clip(vec![
+            Vec2::new(-0.9, -0.9),
+            Vec2::new(0.9, -0.7),
+            Vec2::new(0.1, 0.95),
+        ]);
+        scatter(&mut canvas, 20);
+
+        for (expected, tiled) in render_both(&canvas) {
+            assert!(expected == tiled);
+        }
+    }
+
+    /// Verify that a page is rasterized at the same size as with a [SkiaRenderer].
+    #[test]
+    fn fills_page() {
+        let mut canvas = Canvas::default();
+        scatter(&mut canvas, 10);
+
+        let page = Page::new((30.0, 20.0), crate::Unit::Mm).with_dpi(150.0);
+        let expected = canvas.render(SkiaRenderer::from_page(page, None, true, true));
+        let tiled =
+            canvas.render(TiledSkiaRenderer::from_page(page, None, true, true).with_tile_size(64));
+
+        assert_eq!(tiled.dimensions(), (177, 118));
+        assert!(expected == tiled);
+    }
+}
@@ -0,0 +1,463 @@
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    ops::{Add, AddAssign},
+};
+
+use glam::Vec2;
+
+use crate::Shape;
+
+/// An algorithm for removing points from a polyline while keeping its shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Simplification {
+    /// Ramer–Douglas–Peucker: keep every point further than the tolerance from the simplified line.
+    ///
+    /// Good at keeping sharp corners and peaks, like those in data plots.
+    RamerDouglasPeucker,
+    /// Visvalingam–Whyatt: repeatedly remove the point that forms the smallest triangle with its neighbours,
+    /// until every triangle has an area of at least the tolerance squared.
+    ///
+    /// Gives smoother, more natural results for curves and hand-drawn lines.
+    Visvalingam,
+}
+
+/// How many points a simplification removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SimplifyReport {
+    /// The number of shapes that lost at least one point.
+    pub shapes_changed: usize,
+    /// The number of points before simplifying.
+    pub points_before: usize,
+    /// The number of points after simplifying.
+    pub points_after: usize,
+}
+
+impl SimplifyReport {
+    /// Get the number of points that were removed.
+    pub fn points_removed(&self) -> usize {
+        self.points_before - self.points_after
+    }
+}
+
+impl Add for SimplifyReport {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            shapes_changed: self.shapes_changed + rhs.shapes_changed,
+            points_before: self.points_before + rhs.points_before,
+            points_after: self.points_after + rhs.points_after,
+        }
+    }
+}
+
+impl AddAssign for SimplifyReport {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Shape {
+    /// Remove points that don't change the shape by more than `tolerance`, in the same units as the points.
+    ///
+    /// The first and last points are always kept. Polygons keep at least three corners,
+    /// and are never simplified into an outline that crosses itself.
+    pub fn simplify(&mut self, method: Simplification, tolerance: f32) -> SimplifyReport {
+        let points_before = self.points.len();
+        let closed = self.is_polygon();
+
+        let mut keep = match method {
+            Simplification::RamerDouglasPeucker => ramer_douglas_peucker(&self.points, tolerance),
+            Simplification::Visvalingam => visvalingam(&self.points, tolerance * tolerance),
+        };
+
+        if closed {
+            preserve_topology(&self.points, &mut keep);
+        }
+
+        let mut index = 0;
+        self.points.retain(|_| {
+            index += 1;
+            keep[index - 1]
+        });
+
+        SimplifyReport {
+            shapes_changed: (self.points.len() < points_before) as usize,
+            points_before,
+            points_after: self.points.len(),
+        }
+    }
+}
+
+/// Get the distance from `point` to the segment between `start` and `end`.
+fn segment_distance(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let direction = end - start;
+    let length_squared = direction.length_squared();
+
+    if length_squared == 0.0 {
+        return point.distance(start);
+    }
+
+    let t = ((point - start).dot(direction) / length_squared).clamp(0.0, 1.0);
+    point.distance(start + direction * t)
+}
+
+/// Find the point strictly between `start` and `end` that is furthest from the segment between them.
+fn furthest_between(points: &[Vec2], start: usize, end: usize) -> Option<(usize, f32)> {
+    (start + 1..end)
+        .map(|index| {
+            (
+                index,
+                segment_distance(points[index], points[start], points[end]),
+            )
+        })
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+}
+
+fn ramer_douglas_peucker(points: &[Vec2], tolerance: f32) -> Vec<bool> {
+    let mut keep = vec![false; points.len()];
+    if points.is_empty() {
+        return keep;
+    }
+
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut spans = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = spans.pop() {
+        if let Some((index, distance)) = furthest_between(points, start, end) {
+            if distance > tolerance {
+                keep[index] = true;
+                spans.push((start, index));
+                spans.push((index, end));
+            }
+        }
+    }
+
+    keep
+}
+
+/// A point waiting to be removed, ordered so the smallest area comes first.
+#[derive(PartialEq)]
+struct Candidate {
+    area: f32,
+    index: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .area
+            .partial_cmp(&self.area)
+            .unwrap_or(Ordering::Equal)
+            .then(other.index.cmp(&self.index))
+    }
+}
+
+fn visvalingam(points: &[Vec2], min_area: f32) -> Vec<bool> {
+    let count = points.len();
+    let mut keep = vec![true; count];
+    if count < 3 {
+        return keep;
+    }
+
+    let area = |previous: usize, index: usize, next: usize| {
+        (points[index] - points[previous])
+            .perp_dot(points[next] - points[previous])
+            .abs()
+            / 2.0
+    };
+
+    let mut previous: Vec<usize> = (0..count).map(|index| index.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (1..=count).collect();
+    let mut areas: Vec<f32> = (0..count)
+        .map(|index| match index {
+            0 => f32::INFINITY,
+            index if index == count - 1 => f32::INFINITY,
+            index => area(index - 1, index, index + 1),
+        })
+        .collect();
+    let mut heap: BinaryHeap<Candidate> = areas[1..count - 1]
+        .iter()
+        .enumerate()
+        .map(|(index, area)| Candidate {
+            area: *area,
+            index: index + 1,
+        })
+        .collect();
+
+    while let Some(Candidate {
+        area: smallest,
+        index,
+    }) = heap.pop()
+    {
+        // Skip entries that were removed, or whose area changed since they were queued.
+        if !keep[index] || smallest != areas[index] {
+            continue;
+        }
+        if smallest >= min_area {
+            break;
+        }
+
+        keep[index] = false;
+        let (before, after) = (previous[index], next[index]);
+        next[before] = after;
+        previous[after] = before;
+
+        // Removing a point can't make its neighbours cheaper to remove than it was,
+        // otherwise later removals would undo the order of earlier ones.
+        for neighbour in [before, after] {
+            if neighbour != 0 && neighbour != count - 1 {
+                areas[neighbour] =
+                    area(previous[neighbour], neighbour, next[neighbour]).max(smallest);
+                heap.push(Candidate {
+                    area: areas[neighbour],
+                    index: neighbour,
+                });
+            }
+        }
+    }
+
+    keep
+}
+
+/// Check if the segments `a` and `b` cross each other, not counting shared ends.
+fn segments_cross(a: (Vec2, Vec2), b: (Vec2, Vec2)) -> bool {
+    let side = |start: Vec2, end: Vec2, point: Vec2| (end - start).perp_dot(point - start);
+
+    let (a0, a1) = (side(a.0, a.1, b.0), side(a.0, a.1, b.1));
+    let (b0, b1) = (side(b.0, b.1, a.0), side(b.0, b.1, a.1));
+
+    a0 * a1 < 0.0 && b0 * b1 < 0.0
+}
+
+/// Put back points of a closed polygon until it has at least three corners and its outline doesn't cross itself,
+/// unless the original outline already did.
+fn preserve_topology(points: &[Vec2], keep: &mut [bool]) {
+    let spans = |keep: &[bool]| -> Vec<(usize, usize)> {
+        let kept: Vec<usize> = (0..points.len()).filter(|index| keep[*index]).collect();
+        kept.windows(2).map(|pair| (pair[0], pair[1])).collect()
+    };
+
+    // A polygon needs three corners, plus the point that closes it.
+    loop {
+        let current = spans(keep);
+        if current.len() >= 3 {
+            break;
+        }
+
+        let span = current
+            .into_iter()
+            .filter(|(start, end)| end - start > 1)
+            .max_by_key(|(start, end)| end - start);
+
+        match span.and_then(|(start, end)| furthest_between(points, start, end)) {
+            Some((index, _)) => keep[index] = true,
+            None => return,
+        }
+    }
+
+    let mut pending = spans(keep);
+    let mut grid = SpanGrid::new(points, pending.len());
+    for span in &pending {
+        grid.insert(points, *span);
+    }
+
+    // Putting back a point only changes the two spans either side of it, so only those have to be checked again.
+    while let Some(span) = pending.pop() {
+        if !grid.contains(span) {
+            continue;
+        }
+
+        let crossing = match grid.find_crossing(points, span) {
+            Some(crossing) => crossing,
+            None => continue,
+        };
+
+        // Only simplified spans can be fixed, by putting back one of their points. Fixing the one that skips more
+        // of the outline is more likely to untangle other crossings too.
+        let fixed = if span.1 - span.0 >= crossing.1 - crossing.0 {
+            span
+        } else {
+            crossing
+        };
+        if let Some((index, _)) = furthest_between(points, fixed.0, fixed.1) {
+            keep[index] = true;
+            grid.insert(points, (fixed.0, index));
+            grid.insert(points, (index, fixed.1));
+            pending.extend([(fixed.0, index), (index, fixed.1)]);
+            if fixed != span {
+                pending.push(span);
+            }
+        }
+    }
+}
+
+/// The spans of a simplified outline, listed in each cell of a grid that their bounds overlap,
+/// so the spans that could cross one of them can be found without checking every other span.
+struct SpanGrid {
+    min: Vec2,
+    cell_size: Vec2,
+    /// The number of columns and rows.
+    size: usize,
+    cells: Vec<Vec<(usize, usize)>>,
+    /// The end of the span that starts at each point, if that point is kept.
+    ends: Vec<Option<usize>>,
+}
+
+impl SpanGrid {
+    /// Create an empty grid over `points`, with roughly one cell for each of `spans`.
+    fn new(points: &[Vec2], spans: usize) -> Self {
+        let (min, max) = points.iter().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), point| (min.min(*point), max.max(*point)),
+        );
+        let size = (spans as f32).sqrt().ceil().max(1.0) as usize;
+
+        Self {
+            min,
+            cell_size: ((max - min) / size as f32).max(Vec2::splat(f32::MIN_POSITIVE)),
+            size,
+            cells: vec![Vec::new(); size * size],
+            ends: vec![None; points.len()],
+        }
+    }
+
+    /// Get the cells that the bounds of a span overlap.
+    fn cells(&self, points: &[Vec2], (start, end): (usize, usize)) -> impl Iterator<Item = usize> {
+        let cell = |point: Vec2| {
+            ((point - self.min) / self.cell_size)
+                .floor()
+                .clamp(Vec2::ZERO, Vec2::splat(self.size as f32 - 1.0))
+        };
+        let min = cell(points[start].min(points[end]));
+        let max = cell(points[start].max(points[end]));
+        let size = self.size;
+
+        (min.y as usize..=max.y as usize).flat_map(move |row| {
+            (min.x as usize..=max.x as usize).map(move |column| row * size + column)
+        })
+    }
+
+    /// Add a span, replacing the one that started at the same point.
+    fn insert(&mut self, points: &[Vec2], span: (usize, usize)) {
+        self.ends[span.0] = Some(span.1);
+        for cell in self.cells(points, span).collect::<Vec<_>>() {
+            self.cells[cell].push(span);
+        }
+    }
+
+    /// Check if a span is still part of the outline, rather than split by a point that was put back.
+    fn contains(&self, span: (usize, usize)) -> bool {
+        self.ends[span.0] == Some(span.1)
+    }
+
+    /// Find a span that crosses `span`, where at least one of them is simplified.
+    fn find_crossing(&self, points: &[Vec2], span: (usize, usize)) -> Option<(usize, usize)> {
+        let segment = |(start, end): (usize, usize)| (points[start], points[end]);
+
+        self.cells(points, span)
+            .flat_map(|cell| self.cells[cell].iter().copied())
+            .filter(|other| *other != span && self.contains(*other))
+            .filter(|other| span.1 - span.0 > 1 || other.1 - other.0 > 1)
+            .find(|other| segments_cross(segment(span), segment(*other)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Canvas;
+
+    /// Verify that both algorithms remove noise from a line, but keep its ends and corners.
+    #[test]
+    fn removes_noise() {
+        let mut points: Vec<Vec2> = (0..=100)
+            .map(|n| Vec2::new(n as f32 / 100.0, if n % 2 == 0 { 0.001 } else { -0.001 }))
+            .collect();
+        points.push(Vec2::new(1.0, 1.0));
+
+        // Visvalingam measures area, so the same tolerance lets through noise that is spread further apart.
+        for (method, tolerance) in [
+            (Simplification::RamerDouglasPeucker, 0.01),
+            (Simplification::Visvalingam, 0.05),
+        ] {
+            let mut shape = Shape {
+                points: points.clone(),
+                stroke: None,
+                fill: None,
+                pattern: None,
+                id: None,
+                markers: None,
+            };
+
+            let report = shape.simplify(method, tolerance);
+
+            assert_eq!(shape.points.len(), 3, "{:?}", method);
+            assert_eq!(shape.points[0], points[0]);
+            assert_eq!(shape.points[1].x, 1.0);
+            assert_eq!(shape.points[2], Vec2::new(1.0, 1.0));
+            assert_eq!(report.points_removed(), points.len() - 3);
+            assert_eq!(report.shapes_changed, 1);
+        }
+    }
+
+    /// Verify that polygons stay closed, keep three corners, and don't cross themselves.
+    #[test]
+    fn preserves_polygons() {
+        // A thin "C", where the inner and outer edges are closer together than the tolerance.
+        let mut points = Vec::new();
+        for n in 0..=40 {
+            let angle = n as f32 / 40.0 * 5.0 + 0.6;
+            points.push(Vec2::new(angle.cos(), angle.sin()));
+        }
+        for n in (0..=40).rev() {
+            let angle = n as f32 / 40.0 * 5.0 + 0.6;
+            points.push(Vec2::new(angle.cos(), angle.sin()) * 0.95);
+        }
+        points.push(points[0]);
+
+        let mut canvas = Canvas::default();
+        canvas.zoom_camera(2.0);
+        canvas.draw_shape_absolute(points, None, None);
+        canvas.draw_shape_absolute(
+            vec![Vec2::ZERO, Vec2::X, Vec2::new(0.5, 0.001), Vec2::ZERO],
+            None,
+            None,
+        );
+
+        for method in [
+            Simplification::RamerDouglasPeucker,
+            Simplification::Visvalingam,
+        ] {
+            let mut canvas = canvas.clone();
+            let report = canvas.simplify(method, 0.4);
+            assert!(report.points_removed() > 60, "{:?}", method);
+
+            for shape in canvas.as_raw() {
+                assert!(shape.is_polygon());
+                assert!(shape.points.len() >= 4);
+
+                let segments: Vec<_> = shape
+                    .points
+                    .windows(2)
+                    .map(|pair| (pair[0], pair[1]))
+                    .collect();
+                for (i, a) in segments.iter().enumerate() {
+                    for b in &segments[i + 1..] {
+                        assert!(!segments_cross(*a, *b), "{:?}", method);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use glam::Vec2;
+
+use crate::{Shape, Stroke};
+
+/// A line of text, drawn with a simple built-in stroke font as a polyline for each stroke of each character.
+///
+/// Every character is the same width. Only printable ASCII has glyphs, anything else is drawn as a `?`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Text {
+    /// The text to draw.
+    pub text: String,
+    /// Where to place the text.
+    pub position: Vec2,
+    /// The height of capital letters. Descenders reach a third of this below the baseline.
+    pub height: f32,
+    /// Which point of the text's box goes at [position](Self::position),
+    /// from `(0, 0)` at the start of the baseline to `(1, 1)` at the end of the top of the capitals.
+    pub anchor: Vec2,
+}
+
+/// The width of a glyph, in units of the glyph grid.
+const GLYPH_WIDTH: f32 = 4.0;
+/// The distance from one character to the next, in units of the glyph grid.
+const ADVANCE: f32 = 5.0;
+/// The height of capital letters, in units of the glyph grid.
+const CAP_HEIGHT: f32 = 6.0;
+/// How far the baseline is above the bottom of the glyph grid.
+const BASELINE: f32 = 2.0;
+
+impl Text {
+    /// Create a new [Text] that starts at `position`, sitting on its baseline.
+    pub fn new<S: Into<String>, P: Into<Vec2>>(text: S, position: P, height: f32) -> Self {
+        Self {
+            text: text.into(),
+            position: position.into(),
+            height,
+            anchor: Vec2::ZERO,
+        }
+    }
+
+    /// Modify which point of the text's box goes at its position, consuming the parent.
+    #[inline]
+    pub fn with_anchor<P: Into<Vec2>>(mut self, anchor: P) -> Self {
+        self.anchor = anchor.into();
+        self
+    }
+
+    /// Get the width of the text and the height of its capitals.
+    pub fn size(&self) -> Vec2 {
+        let count = self.text.chars().count();
+        if count == 0 {
+            return Vec2::new(0.0, self.height);
+        }
+
+        Vec2::new(ADVANCE * (count - 1) as f32 + GLYPH_WIDTH, CAP_HEIGHT) * self.unit()
+    }
+
+    /// Get the polyline of each stroke of each character, in the same space as the text's position.
+    pub fn strokes(&self) -> Vec<Vec<Vec2>> {
+        let unit = self.unit();
+        let origin = self.position - self.size() * self.anchor;
+
+        self.text
+            .chars()
+            .enumerate()
+            .flat_map(|(index, character)| {
+                let offset = Vec2::new(ADVANCE * index as f32, -BASELINE);
+
+                glyph(character).split_whitespace().map(move |stroke| {
+                    stroke
+                        .as_bytes()
+                        .chunks(2)
+                        .map(|digits| {
+                            let point =
+                                Vec2::new((digits[0] - b'0') as f32, (digits[1] - b'0') as f32);
+                            origin + (point + offset) * unit
+                        })
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
+    /// Get a stroked [Shape] for each stroke of each character, in the same space as the text's position.
+    pub fn shapes(&self, stroke: Stroke) -> Vec<Shape> {
+        self.strokes()
+            .into_iter()
+            .map(|points| Shape {
+                points,
+                stroke: Some(stroke),
+                fill: None,
+                pattern: None,
+                id: None,
+                markers: None,
+            })
+            .collect()
+    }
+
+    /// Get the size of a unit of the glyph grid.
+    fn unit(&self) -> f32 {
+        self.height / CAP_HEIGHT
+    }
+}
+
+/// Get the strokes of a character.
+///
+/// Each stroke is a run of points, each written as two digits for x and y on a grid that is 4 wide.
+/// The baseline is at y = 2, lowercase letters reach y = 6 and capitals reach y = 8.
+fn glyph(character: char) -> &'static str {
+    match character {
+        ' ' => "",
+        '!' => "2824 2322",
+        '"' => "1817 3837",
+        '#' => "1812 3832 0646 0444",
+        '$' => "473818070615354443321203 2921",
+        '%' => "0248 0818170708 3343423233",
+        '&' => "4216172837360403122244",
+        '\'' => "2827",
+        '(' => "38272332",
+        ')' => "18272312",
+        '*' => "2723 0644 0446",
+        '+' => "2622 0444",
+        ',' => "2211",
+        '-' => "1434",
+        '.' => "2223",
+        '/' => "0248",
+        '0' => "123243473818070312 1337",
+        '1' => "172822 1232",
+        '2' => "07183847460242",
+        '3' => "07183847463515 354443321203",
+        '4' => "32380444",
+        '5' => "480805354443321203",
+        '6' => "38180703123243443505",
+        '7' => "084812",
+        '8' => "150607183847463515 1504031232434435",
+        '9' => "45150607183847433212",
+        ':' => "2223 2526",
+        ';' => "2211 2526",
+        '<' => "460442",
+        '=' => "0343 0545",
+        '>' => "064402",
+        '?' => "071838474624 2322",
+        '@' => "3436161434 344447381807031242",
+        'A' => "022842 1535",
+        'B' => "02083847463505 3544433202",
+        'C' => "4738180703123243",
+        'D' => "02082846442202",
+        'E' => "48080242 0535",
+        'F' => "480802 0535",
+        'G' => "47381807031232434525",
+        'H' => "0208 4842 0545",
+        'I' => "1838 2822 1232",
+        'J' => "4843321203",
+        'K' => "0208 4804 1542",
+        'L' => "080242",
+        'M' => "0208244842",
+        'N' => "02084248",
+        'O' => "123243473818070312",
+        'P' => "02083847463505",
+        'Q' => "123243473818070312 2442",
+        'R' => "02083847463505 2542",
+        'S' => "473818070615354443321203",
+        'T' => "0848 2822",
+        'U' => "080312324348",
+        'V' => "082248",
+        'W' => "0812253248",
+        'X' => "0248 0842",
+        'Y' => "082548 2522",
+        'Z' => "08480242",
+        '[' => "38282232",
+        '\\' => "0842",
+        ']' => "18282212",
+        '^' => "062846",
+        '_' => "0141",
+        '`' => "1827",
+        'a' => "16364542 441403123243",
+        'b' => "0802 0516364543321203",
+        'c' => "4536160503123243",
+        'd' => "4842 4536160503123243",
+        'e' => "04444536160503123243",
+        'f' => "38281712 0636",
+        'g' => "4641301001 4536160504133344",
+        'h' => "0802 0516364542",
+        'i' => "2622 2728",
+        'j' => "3631201001 3738",
+        'k' => "0802 4604 2542",
+        'l' => "182822 1232",
+        'm' => "0602 05162522 25364542",
+        'n' => "0602 0516364542",
+        'o' => "123243453616050312",
+        'p' => "0600 0516364543321203",
+        'q' => "4640 4536160503123243",
+        'r' => "0602 042646",
+        's' => "45361605143443321203",
+        't' => "18132232 0636",
+        'u' => "0603123243 4642",
+        'v' => "062246",
+        'w' => "0612243246",
+        'x' => "0246 0642",
+        'y' => "0622 4610",
+        'z' => "06460242",
+        '{' => "38272615242332",
+        '|' => "2921",
+        '}' => "18272635242312",
+        '~' => "05163445",
+        _ => glyph('?'),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that every printable character has a glyph on the grid, and that text is laid out around its anchor.
+    #[test]
+    fn lays_out_glyphs() {
+        for character in ' '..='~' {
+            for stroke in glyph(character).split_whitespace() {
+                assert!(
+                    stroke.len() >= 4 && stroke.len() % 2 == 0,
+                    "{:?}",
+                    character
+                );
+                for digits in stroke.as_bytes().chunks(2) {
+                    assert!(digits[0] <= b'4' && digits[1] <= b'9', "{:?}", character);
+                }
+            }
+        }
+        assert_eq!(glyph('é'), glyph('?'));
+
+        let text = Text::new("Hi", (1.0, 1.0), 0.6).with_anchor((0.5, 1.0));
+        assert!(text.size().abs_diff_eq(Vec2::new(0.9, 0.6), 0.0001));
+
+        let strokes = text.strokes();
+        assert_eq!(strokes.len(), 5);
+        // The left side of the H runs up from the baseline to the top of the capitals.
+        assert!(strokes[0][0].abs_diff_eq(Vec2::new(0.55, 0.4), 0.0001));
+        assert!(strokes[0][1].abs_diff_eq(Vec2::new(0.55, 1.0), 0.0001));
+    }
+}
@@ -0,0 +1,370 @@
+use glam::Vec2;
+
+use crate::measure::resample;
+use crate::{Color, Shape, Stroke};
+
+/// A value that can be smoothly interpolated towards another of its kind.
+pub trait Interpolate {
+    /// Interpolate between `self` and `other`.
+    ///
+    /// A `t` of `0.0` returns `self` and a `t` of `1.0` returns `other`.
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+
+    /// Interpolate between `self` and `other`, with `t` shaped by an [Easing] curve.
+    fn tween(&self, other: &Self, t: f32, easing: Easing) -> Self
+    where
+        Self: Sized,
+    {
+        self.interpolate(other, easing.ease(t))
+    }
+}
+
+impl Interpolate for f32 {
+    #[inline]
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for Vec2 {
+    #[inline]
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+/// Interpolates each channel independently, in sRGB.
+///
+/// For blends in linear light or in a perceptual space, use [Color::mix_linear] or [Color::mix_oklab].
+impl Interpolate for Color {
+    #[inline]
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        *self + (*other - *self) * t
+    }
+}
+
+impl Color {
+    /// Interpolate between two colors in the Oklab color space.
+    ///
+    /// This is the same as [Color::mix_oklab], which replaced it.
+    #[deprecated(note = "use `Color::mix_oklab` instead")]
+    #[inline]
+    pub fn interpolate_perceptual(&self, other: &Self, t: f32) -> Self {
+        self.mix_oklab(other, t)
+    }
+}
+
+/// The [LineEnd](crate::LineEnd) switches halfway through.
+impl Interpolate for Stroke {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Stroke {
+            color: self.color.interpolate(&other.color, t),
+            width: self.width.interpolate(&other.width, t),
+            line_end: if t < 0.5 {
+                self.line_end
+            } else {
+                other.line_end
+            },
+        }
+    }
+}
+
+/// Morphs one shape into another.
+///
+/// If the shapes have different numbers of points, both are first resampled along their length to a common count.
+/// A stroke or fill that only one shape has fades in or out, while the [pattern](Shape::pattern), [id](Shape::id) and [markers](Shape::markers) switch halfway through.
+impl Interpolate for Shape {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        let points = if self.points.len() == other.points.len() {
+            self.points
+                .iter()
+                .zip(&other.points)
+                .map(|(a, b)| a.interpolate(b, t))
+                .collect()
+        } else {
+            let count = self.points.len().max(other.points.len());
+
+            resample(&self.points, count)
+                .iter()
+                .zip(resample(&other.points, count))
+                .map(|(a, b)| a.interpolate(&b, t))
+                .collect()
+        };
+
+        let faded = |stroke: Stroke| Stroke {
+            color: stroke.color.with_a(0.0),
+            ..stroke
+        };
+
+        let stroke = match (self.stroke, other.stroke) {
+            (Some(a), Some(b)) => Some(a.interpolate(&b, t)),
+            (Some(a), None) => Some(a.interpolate(&faded(a), t)),
+            (None, Some(b)) => Some(faded(b).interpolate(&b, t)),
+            (None, None) => None,
+        };
+
+        let fill = match (self.fill, other.fill) {
+            (Some(a), Some(b)) => Some(a.interpolate(&b, t)),
+            (Some(a), None) => Some(a.interpolate(&a.with_a(0.0), t)),
+            (None, Some(b)) => Some(b.with_a(0.0).interpolate(&b, t)),
+            (None, None) => None,
+        };
+
+        let step = if t < 0.5 { self } else { other };
+
+        Shape {
+            points,
+            stroke,
+            fill,
+            pattern: step.pattern.clone(),
+            id: step.id.clone(),
+            markers: step.markers.clone(),
+        }
+    }
+}
+
+/// Curves that shape the progress of an animation.
+///
+/// Each takes a `t` from `0.0` to `1.0` and returns the eased progress, which starts at `0.0` and ends at `1.0`.
+/// Some curves, like [BackIn](Easing::BackIn) and [ElasticOut](Easing::ElasticOut), overshoot in between.
+///
+/// Formulas from [easings.net](https://easings.net).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Hold the start value until the end.
+    Step,
+    /// Accelerate quadratically.
+    QuadIn,
+    /// Decelerate quadratically.
+    QuadOut,
+    /// Accelerate, then decelerate quadratically.
+    QuadInOut,
+    /// Accelerate cubically.
+    CubicIn,
+    /// Decelerate cubically.
+    CubicOut,
+    /// Accelerate, then decelerate cubically.
+    CubicInOut,
+    /// Accelerate along a sine wave.
+    SineIn,
+    /// Decelerate along a sine wave.
+    SineOut,
+    /// Accelerate, then decelerate along a sine wave.
+    SineInOut,
+    /// Accelerate exponentially.
+    ExpoIn,
+    /// Decelerate exponentially.
+    ExpoOut,
+    /// Accelerate, then decelerate exponentially.
+    ExpoInOut,
+    /// Pull back slightly before accelerating.
+    BackIn,
+    /// Overshoot slightly before settling.
+    BackOut,
+    /// Pull back, then overshoot.
+    BackInOut,
+    /// Oscillate past the end before settling, like a spring.
+    ElasticOut,
+    /// Bounce against the end, like a dropped ball.
+    BounceOut,
+    /// A CSS-style cubic bezier timing function through `(0, 0)`, `(x1, y1)`, `(x2, y2)` and `(1, 1)`.
+    ///
+    /// `x1` and `x2` should be within `0.0..=1.0`.
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl Easing {
+    /// Apply the curve to `t`, which is clamped to `0.0..=1.0`.
+    pub fn ease(self, t: f32) -> f32 {
+        use std::f32::consts::PI;
+
+        const BACK: f32 = 1.70158;
+
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::Step => {
+                if t < 1.0 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Easing::SineOut => (t * PI / 2.0).sin(),
+            Easing::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+            Easing::ExpoIn => {
+                if t == 0.0 {
+                    0.0
+                } else {
+                    2f32.powf(10.0 * t - 10.0)
+                }
+            }
+            Easing::ExpoOut => {
+                if t == 1.0 {
+                    1.0
+                } else {
+                    1.0 - 2f32.powf(-10.0 * t)
+                }
+            }
+            Easing::ExpoInOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else if t < 0.5 {
+                    2f32.powf(20.0 * t - 10.0) / 2.0
+                } else {
+                    (2.0 - 2f32.powf(-20.0 * t + 10.0)) / 2.0
+                }
+            }
+            Easing::BackIn => (BACK + 1.0) * t * t * t - BACK * t * t,
+            Easing::BackOut => 1.0 + (BACK + 1.0) * (t - 1.0).powi(3) + BACK * (t - 1.0).powi(2),
+            Easing::BackInOut => {
+                let c = BACK * 1.525;
+
+                if t < 0.5 {
+                    (2.0 * t).powi(2) * ((c + 1.0) * 2.0 * t - c) / 2.0
+                } else {
+                    ((2.0 * t - 2.0).powi(2) * ((c + 1.0) * (t * 2.0 - 2.0) + c) + 2.0) / 2.0
+                }
+            }
+            Easing::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+                }
+            }
+            Easing::BounceOut => {
+                const N: f32 = 7.5625;
+                const D: f32 = 2.75;
+
+                if t < 1.0 / D {
+                    N * t * t
+                } else if t < 2.0 / D {
+                    let t = t - 1.5 / D;
+                    N * t * t + 0.75
+                } else if t < 2.5 / D {
+                    let t = t - 2.25 / D;
+                    N * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D;
+                    N * t * t + 0.984375
+                }
+            }
+            Easing::CubicBezier(x1, y1, x2, y2) => {
+                let bezier = |a: f32, b: f32, s: f32| {
+                    3.0 * a * s * (1.0 - s).powi(2) + 3.0 * b * s * s * (1.0 - s) + s * s * s
+                };
+
+                // Find the curve parameter that lands on `t` along the x axis by bisection.
+                let mut low = 0.0;
+                let mut high = 1.0;
+                for _ in 0..32 {
+                    let mid = (low + high) / 2.0;
+                    if bezier(x1, x2, mid) < t {
+                        low = mid;
+                    } else {
+                        high = mid;
+                    }
+                }
+
+                bezier(y1, y2, (low + high) / 2.0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineEnd;
+
+    /// Verify that every easing curve starts at 0 and ends at 1.
+    #[test]
+    fn easing_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::Step,
+            Easing::QuadInOut,
+            Easing::CubicInOut,
+            Easing::SineInOut,
+            Easing::ExpoInOut,
+            Easing::BackInOut,
+            Easing::ElasticOut,
+            Easing::BounceOut,
+            Easing::CubicBezier(0.25, 0.1, 0.25, 1.0),
+        ] {
+            assert!(easing.ease(0.0).abs() < 0.001, "{:?}", easing);
+            assert!((easing.ease(1.0) - 1.0).abs() < 0.001, "{:?}", easing);
+        }
+    }
+
+    /// Verify that shapes with different point counts are resampled before morphing.
+    #[test]
+    fn morph_resamples() {
+        let stroke = Some(Stroke::new(Color::black(), 0.1, LineEnd::Round));
+        let line = Shape {
+            points: vec![Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0)],
+            stroke,
+            fill: None,
+            pattern: None,
+            id: None,
+            markers: None,
+        };
+        let bent = Shape {
+            points: vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(2.0, 0.0),
+            ],
+            stroke,
+            fill: None,
+            pattern: None,
+            id: None,
+            markers: None,
+        };
+
+        let halfway = line.interpolate(&bent, 0.5);
+
+        assert_eq!(
+            halfway.points,
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(1.0, 0.5),
+                Vec2::new(2.0, 0.0)
+            ]
+        );
+    }
+
+    /// Verify that perceptual interpolation between black and white lands on Oklab's middle lightness, not the sRGB midpoint.
+    #[test]
+    fn perceptual_midpoint() {
+        let rgb = Color::black().interpolate(&Color::white(), 0.5);
+        let perceptual = Color::black().mix_oklab(&Color::white(), 0.5);
+
+        assert!((rgb.r() - 0.5).abs() < 0.001);
+        assert!((perceptual.r() - 0.389).abs() < 0.001);
+        assert!((perceptual.r() - perceptual.b()).abs() < 0.001);
+    }
+}